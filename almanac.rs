@@ -0,0 +1,353 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::phenomena::pheno_ut;
+use crate::position::calc_ut;
+use crate::raw;
+use crate::solver::{altitude, azalt_flag};
+use crate::tuple_result;
+
+// Return code of swe_rise_trans when the body does not cross the horizon
+const CIRCUMPOLAR: i32 = -2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RiseTransEvent {
+    Rise,
+    Set,
+    // Crossing of the meridian above and below the horizon
+    UpperTransit,
+    LowerTransit,
+}
+
+impl RiseTransEvent {
+    // The SE_CALC_* bit for rsmi
+    pub fn flag(self) -> i32 {
+        let flag = match self {
+            RiseTransEvent::Rise => raw::SE_CALC_RISE,
+            RiseTransEvent::Set => raw::SE_CALC_SET,
+            RiseTransEvent::UpperTransit => raw::SE_CALC_MTRANSIT,
+            RiseTransEvent::LowerTransit => raw::SE_CALC_ITRANSIT,
+        };
+        flag as i32
+    }
+}
+
+// The point of the disc that touches the horizon at rise and set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Disc {
+    UpperLimb,
+    Center,
+    LowerLimb,
+}
+
+// Rise and set of the Sun's center at 6, 12 or 18 degrees below the horizon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Twilight {
+    Civil,
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    fn flag(self) -> u32 {
+        match self {
+            Twilight::Civil => raw::SE_BIT_CIVIL_TWILIGHT,
+            Twilight::Nautical => raw::SE_BIT_NAUTIC_TWILIGHT,
+            Twilight::Astronomical => raw::SE_BIT_ASTRO_TWILIGHT,
+        }
+    }
+
+    // Altitude of the Sun's center in degrees
+    pub fn depression(self) -> f64 {
+        match self {
+            Twilight::Civil => -6.,
+            Twilight::Nautical => -12.,
+            Twilight::Astronomical => -18.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseTransOptions {
+    pub disc: Disc,
+    pub refraction: bool,
+    // Atmospheric pressure in hPa, 0 to estimate it from the altitude of the location,
+    // and temperature in degrees Celsius, for the refraction
+    pub pressure: f64,
+    pub temperature: f64,
+    // Altitude of the local horizon in degrees, for swe_rise_trans_true_hor
+    pub horizon: f64,
+    // Sun only, overrides disc and horizon
+    pub twilight: Option<Twilight>,
+}
+
+impl Default for RiseTransOptions {
+    // Upper limb on a flat horizon with standard refraction, as in almanacs
+    fn default() -> Self {
+        RiseTransOptions {
+            disc: Disc::UpperLimb,
+            refraction: true,
+            pressure: 0.,
+            temperature: 10.,
+            horizon: 0.,
+            twilight: None,
+        }
+    }
+}
+
+impl RiseTransOptions {
+    fn flags(&self) -> i32 {
+        let mut flags = match self.disc {
+            Disc::UpperLimb => 0,
+            Disc::Center => raw::SE_BIT_DISC_CENTER,
+            Disc::LowerLimb => raw::SE_BIT_DISC_BOTTOM,
+        };
+        if !self.refraction {
+            flags |= raw::SE_BIT_NO_REFRACTION;
+        }
+        if let Some(twilight) = self.twilight {
+            flags |= twilight.flag();
+        }
+        flags as i32
+    }
+
+    fn horizon(&self) -> f64 {
+        match self.twilight {
+            Some(twilight) => twilight.depression(),
+            None => self.horizon,
+        }
+    }
+}
+
+// When the library can not find a rise or set it returns -2, here split by whether the
+// body stays above or below the horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RiseTrans {
+    At(f64),
+    Circumpolar,
+    NeverRises,
+}
+
+impl RiseTrans {
+    pub fn time(self) -> Option<f64> {
+        match self {
+            RiseTrans::At(tjd_ut) => Some(tjd_ut),
+            _ => None,
+        }
+    }
+}
+
+// Altitude of the point of the disc that rises and sets over the horizon of options, as
+// the library compares them
+fn altitude_over_horizon(
+    body: Body,
+    tjd_ut: f64,
+    location: &GeoLocation,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<f64, SweError> {
+    // twilights use the true altitude of the center
+    if options.twilight.is_some() {
+        return altitude(body, *location, iflag, options.horizon())(tjd_ut);
+    }
+
+    let p = calc_ut(body, tjd_ut, iflag)?;
+    let xaz = unsafe {
+        tuple_result::swe_azalt(
+            tjd_ut,
+            azalt_flag(iflag),
+            location.to_geopos(),
+            options.pressure,
+            options.temperature,
+            [p.longitude, p.latitude, p.distance],
+        )
+    };
+    let center = if options.refraction { xaz[2] } else { xaz[1] };
+    let radius = match options.disc {
+        Disc::UpperLimb => pheno_ut(body, tjd_ut, iflag)?.diameter / 2.,
+        Disc::Center => 0.,
+        Disc::LowerLimb => -pheno_ut(body, tjd_ut, iflag)?.diameter / 2.,
+    };
+
+    Ok(center + radius - options.horizon)
+}
+
+// Next rise, set or meridian transit of body after tjd_ut (UT)
+pub fn rise_trans(
+    body: Body,
+    tjd_ut: f64,
+    event: RiseTransEvent,
+    location: &GeoLocation,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<RiseTrans, SweError> {
+    // with a horizon of 0 this is the same as swe_rise_trans
+    let (ret, tret) = unsafe {
+        tuple_result::swe_rise_trans_true_hor(
+            tjd_ut,
+            body.ipl(),
+            "",
+            iflag,
+            event.flag() | options.flags(),
+            location.to_geopos(),
+            options.pressure,
+            options.temperature,
+            options.horizon,
+        )?
+    };
+    if ret != CIRCUMPOLAR {
+        return Ok(RiseTrans::At(tret));
+    }
+
+    let above = altitude_over_horizon(body, tjd_ut, location, options, iflag)? > 0.;
+    Ok(if above {
+        RiseTrans::Circumpolar
+    } else {
+        RiseTrans::NeverRises
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyRiseSet {
+    pub body: Body,
+    pub rise: RiseTrans,
+    pub transit: RiseTrans,
+    pub set: RiseTrans,
+}
+
+fn body_rise_set(
+    body: Body,
+    tjd_ut: f64,
+    location: &GeoLocation,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<BodyRiseSet, SweError> {
+    let event = |event| rise_trans(body, tjd_ut, event, location, options, iflag);
+    Ok(BodyRiseSet {
+        body,
+        rise: event(RiseTransEvent::Rise)?,
+        transit: event(RiseTransEvent::UpperTransit)?,
+        set: event(RiseTransEvent::Set)?,
+    })
+}
+
+// Twilights begin in the morning (dawn) and end in the evening (dusk)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwilightTimes {
+    pub twilight: Twilight,
+    pub dawn: RiseTrans,
+    pub dusk: RiseTrans,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Almanac {
+    pub tjd_ut: f64,
+    pub location: GeoLocation,
+    pub sun: BodyRiseSet,
+    // Civil, nautical and astronomical
+    pub twilights: [TwilightTimes; 3],
+    pub moon: BodyRiseSet,
+    // Mercury to Pluto
+    pub planets: Vec<BodyRiseSet>,
+}
+
+impl Almanac {
+    pub fn twilight(&self, twilight: Twilight) -> &TwilightTimes {
+        &self.twilights[twilight as usize]
+    }
+
+    pub fn planet(&self, body: Body) -> Option<&BodyRiseSet> {
+        self.planets.iter().find(|p| p.body == body)
+    }
+}
+
+// The events of a day at location. tjd_ut should be the local midnight starting the day
+// (UT); every event is the first one after it, so a moonrise or moonset that is skipped
+// on this day is the one of the next day.
+pub fn almanac(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<Almanac, SweError> {
+    let mut twilights = Vec::with_capacity(3);
+    for twilight in [Twilight::Civil, Twilight::Nautical, Twilight::Astronomical] {
+        let options = RiseTransOptions {
+            twilight: Some(twilight),
+            ..*options
+        };
+        let event = |event| rise_trans(Body::Sun, tjd_ut, event, location, &options, iflag);
+        twilights.push(TwilightTimes {
+            twilight,
+            dawn: event(RiseTransEvent::Rise)?,
+            dusk: event(RiseTransEvent::Set)?,
+        });
+    }
+
+    let options = RiseTransOptions {
+        twilight: None,
+        ..*options
+    };
+    let planets = Body::PLANETS[2..]
+        .iter()
+        .map(|&body| body_rise_set(body, tjd_ut, location, &options, iflag))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Almanac {
+        tjd_ut,
+        location: *location,
+        sun: body_rise_set(Body::Sun, tjd_ut, location, &options, iflag)?,
+        twilights: [twilights[0], twilights[1], twilights[2]],
+        moon: body_rise_set(Body::Moon, tjd_ut, location, &options, iflag)?,
+        planets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sunrise_and_sunset_in_greenwich() {
+        // 2022-06-21, sunrise 3:43 and sunset 20:21 UT
+        let greenwich = GeoLocation::new(0., 51.48, 0.);
+        let day = almanac(2459751.5, &greenwich, &RiseTransOptions::default(), 0).unwrap();
+
+        let (rise, set) = (day.sun.rise.time().unwrap(), day.sun.set.time().unwrap());
+        assert!((rise - 2459751.655).abs() < 0.002);
+        assert!((set - 2459752.348).abs() < 0.002);
+
+        let civil = day.twilight(Twilight::Civil);
+        assert!(civil.dawn.time().unwrap() < rise);
+        assert!(civil.dusk.time().unwrap() > set);
+        // the Sun stays above -18 degrees at midsummer
+        let astronomical = day.twilight(Twilight::Astronomical);
+        assert_eq!(astronomical.dawn, RiseTrans::Circumpolar);
+        assert_eq!(day.planets.len(), 8);
+    }
+
+    #[test]
+    fn midnight_sun_and_polar_night() {
+        let tromso = GeoLocation::new(18.96, 69.65, 0.);
+        let options = RiseTransOptions::default();
+
+        let summer = rise_trans(
+            Body::Sun,
+            2459751.5,
+            RiseTransEvent::Rise,
+            &tromso,
+            &options,
+            0,
+        );
+        assert_eq!(summer.unwrap(), RiseTrans::Circumpolar);
+        let winter = rise_trans(
+            Body::Sun,
+            2459934.5,
+            RiseTransEvent::Rise,
+            &tromso,
+            &options,
+            0,
+        );
+        assert_eq!(winter.unwrap(), RiseTrans::NeverRises);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::{calc_ut, Position};
+use crate::raw;
+use crate::solver::{normalize_angle, EventKind, Solver};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AspectKind {
+    Conjunction,
+    SemiSextile,
+    SemiSquare,
+    Sextile,
+    Quintile,
+    Square,
+    Trine,
+    Sesquiquadrate,
+    BiQuintile,
+    Quincunx,
+    Opposition,
+    // multiple * 360 / harmonic degrees, e.g. the septile is harmonic 7, multiple 1
+    Harmonic { harmonic: u32, multiple: u32 },
+    // Any angle in degrees
+    Custom(f64),
+}
+
+impl AspectKind {
+    // Angle between 0 and 180 degrees
+    pub fn angle(self) -> f64 {
+        let angle = match self {
+            AspectKind::Conjunction => 0.,
+            AspectKind::SemiSextile => 30.,
+            AspectKind::SemiSquare => 45.,
+            AspectKind::Sextile => 60.,
+            AspectKind::Quintile => 72.,
+            AspectKind::Square => 90.,
+            AspectKind::Trine => 120.,
+            AspectKind::Sesquiquadrate => 135.,
+            AspectKind::BiQuintile => 144.,
+            AspectKind::Quincunx => 150.,
+            AspectKind::Opposition => 180.,
+            AspectKind::Harmonic { harmonic, multiple } => 360. * multiple as f64 / harmonic as f64,
+            AspectKind::Custom(angle) => angle,
+        };
+
+        normalize_angle(angle).abs()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectDef {
+    pub kind: AspectKind,
+    // Allowed deviation from the exact angle in degrees
+    pub orb: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AspectSet {
+    pub aspects: Vec<AspectDef>,
+}
+
+impl AspectSet {
+    pub fn new() -> Self {
+        AspectSet::default()
+    }
+
+    // Conjunction, opposition, trine, square and sextile
+    pub fn major() -> Self {
+        AspectSet::new()
+            .with(AspectKind::Conjunction, 8.)
+            .with(AspectKind::Opposition, 8.)
+            .with(AspectKind::Trine, 7.)
+            .with(AspectKind::Square, 7.)
+            .with(AspectKind::Sextile, 5.)
+    }
+
+    pub fn minor() -> Self {
+        AspectSet::new()
+            .with(AspectKind::Quincunx, 3.)
+            .with(AspectKind::SemiSextile, 2.)
+            .with(AspectKind::SemiSquare, 2.)
+            .with(AspectKind::Sesquiquadrate, 2.)
+            .with(AspectKind::Quintile, 2.)
+            .with(AspectKind::BiQuintile, 2.)
+    }
+
+    pub fn major_and_minor() -> Self {
+        let mut set = AspectSet::major();
+        set.aspects.extend(AspectSet::minor().aspects);
+        set
+    }
+
+    // All aspects of a harmonic that are not part of a lower harmonic, e.g. for 7 the
+    // septile, bi-septile and tri-septile. Panics for harmonic 0.
+    pub fn harmonic(harmonic: u32, orb: f64) -> Self {
+        assert!(harmonic > 0, "harmonic must be greater than zero");
+        let mut set = AspectSet::new();
+        for multiple in 1..=harmonic / 2 {
+            if gcd(multiple, harmonic) == 1 {
+                set = set.with(AspectKind::Harmonic { harmonic, multiple }, orb);
+            }
+        }
+        set
+    }
+
+    // Panics for a harmonic aspect with harmonic or multiple 0
+    pub fn with(mut self, kind: AspectKind, orb: f64) -> Self {
+        if let AspectKind::Harmonic { harmonic, multiple } = kind {
+            assert!(
+                harmonic > 0 && multiple > 0,
+                "harmonic and multiple must be greater than zero"
+            );
+        }
+        self.aspects.push(AspectDef { kind, orb });
+        self
+    }
+
+    // The aspect closest to exact between two longitudes, if any is within its orb.
+    // Returns the aspect and the deviation from exact in degrees.
+    pub fn find(&self, a: f64, b: f64) -> Option<(AspectDef, f64)> {
+        let separation = normalize_angle(a - b).abs();
+
+        self.aspects
+            .iter()
+            .map(|def| (*def, separation - def.kind.angle()))
+            .filter(|(def, deviation)| deviation.abs() <= def.orb)
+            .min_by(|x, y| x.1.abs().total_cmp(&y.1.abs()))
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectState {
+    Applying,
+    Exact,
+    Separating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectMatch {
+    pub aspect: AspectDef,
+    // Separation minus the exact angle, negative when the separation is below it
+    pub deviation: f64,
+    pub state: AspectState,
+}
+
+// Deviation below which an aspect is reported as exact
+const EXACT: f64 = 1e-6;
+
+// Aspect between two positions at one time. Applying or separating is decided by the
+// longitude speeds, so they should be calculated with SEFLG_SPEED.
+pub fn aspect_between(a: &Position, b: &Position, set: &AspectSet) -> Option<AspectMatch> {
+    let (aspect, deviation) = set.find(a.longitude, b.longitude)?;
+    Some(AspectMatch {
+        aspect,
+        deviation,
+        state: aspect_state(a, b, deviation),
+    })
+}
+
+fn aspect_state(a: &Position, b: &Position, deviation: f64) -> AspectState {
+    // rate of change of the unsigned separation
+    let separation = normalize_angle(a.longitude - b.longitude);
+    let rate = separation.signum() * (a.longitude_speed - b.longitude_speed);
+
+    if deviation.abs() < EXACT {
+        AspectState::Exact
+    } else if deviation * rate < 0. {
+        AspectState::Applying
+    } else {
+        AspectState::Separating
+    }
+}
+
+// What a moving body is aspecting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Body(Body),
+    // A fixed ecliptic longitude, e.g. a natal planet or angle
+    Longitude(f64),
+}
+
+impl From<Body> for Target {
+    fn from(body: Body) -> Self {
+        Target::Body(body)
+    }
+}
+
+impl Target {
+    pub(crate) fn position(&self, tjd_ut: f64, iflag: i32) -> Result<Position, SweError> {
+        match self {
+            Target::Body(body) => calc_ut(*body, tjd_ut, iflag),
+            Target::Longitude(longitude) => Ok(Position {
+                longitude: *longitude,
+                ..Position::default()
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectEvent {
+    pub tjd_ut: f64,
+    pub aspect: AspectDef,
+    // Exact separation, longitude of a minus longitude of b: +angle or -angle
+    pub separation: f64,
+    // Retrograde motion can make the same aspect exact up to three times in a row;
+    // pass numbers them from 1 and passes is the size of the group
+    pub pass: u32,
+    pub passes: u32,
+    // State of the aspect as aspect_between decides it a minute before and after the
+    // exact time: applying then separating, unless a station falls on the aspect
+    pub before: AspectState,
+    pub after: AspectState,
+}
+
+// Time from the exact aspect at which AspectEvent::before and after are taken, in days
+const STATE_OFFSET: f64 = 1. / 1440.;
+
+fn state_at(
+    a: Body,
+    b: &Target,
+    angle: f64,
+    tjd_ut: f64,
+    iflag: i32,
+) -> Result<AspectState, SweError> {
+    let iflag = iflag | raw::SEFLG_SPEED as i32;
+    let pa = calc_ut(a, tjd_ut, iflag)?;
+    let pb = b.position(tjd_ut, iflag)?;
+    let deviation = normalize_angle(pa.longitude - pb.longitude).abs() - angle;
+    Ok(aspect_state(&pa, &pb, deviation))
+}
+
+// Exact times of every aspect in the set between body a and target b from start to end
+// (UT), sorted by time. step should be shorter than the time the separation needs to go
+// through the aspect and back, a day is fine for everything but the Moon against a
+// retrograde station.
+pub fn find_aspects(
+    a: Body,
+    b: Target,
+    set: &AspectSet,
+    start: f64,
+    end: f64,
+    iflag: i32,
+    step: f64,
+) -> Result<Vec<AspectEvent>, SweError> {
+    let iflag = iflag & !(raw::SEFLG_SPEED as i32);
+    let solver = Solver::new(step).angular(true);
+    let mut events = Vec::new();
+
+    for def in &set.aspects {
+        let angle = def.kind.angle();
+        let mut separations = vec![angle];
+        if angle > EXACT && angle < 180. - EXACT {
+            separations.push(-angle);
+        }
+
+        for separation in separations {
+            let f = |t: f64| {
+                let pa = calc_ut(a, t, iflag)?;
+                let pb = b.position(t, iflag)?;
+                Ok(normalize_angle(pa.longitude - pb.longitude - separation))
+            };
+            let roots = solver.roots(f, start, end)?;
+
+            // passes of a retrograde loop alternate in direction
+            let mut group: Vec<AspectEvent> = Vec::new();
+            let mut previous: Option<EventKind> = None;
+            for root in roots {
+                if previous == Some(root.kind) {
+                    close_group(&mut group, &mut events);
+                }
+                previous = Some(root.kind);
+                group.push(AspectEvent {
+                    tjd_ut: root.tjd,
+                    aspect: *def,
+                    separation,
+                    pass: group.len() as u32 + 1,
+                    passes: 0,
+                    before: state_at(a, &b, angle, root.tjd - STATE_OFFSET, iflag)?,
+                    after: state_at(a, &b, angle, root.tjd + STATE_OFFSET, iflag)?,
+                });
+            }
+            close_group(&mut group, &mut events);
+        }
+    }
+
+    events.sort_by(|x, y| x.tjd_ut.total_cmp(&y.tjd_ut));
+    Ok(events)
+}
+
+fn close_group(group: &mut Vec<AspectEvent>, events: &mut Vec<AspectEvent>) {
+    let passes = group.len() as u32;
+    events.extend(group.drain(..).map(|e| AspectEvent { passes, ..e }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_and_separating() {
+        let set = AspectSet::major();
+        let fast = |longitude| Position {
+            longitude,
+            longitude_speed: 1.,
+            ..Position::default()
+        };
+        let fixed = Position {
+            longitude: 100.,
+            ..Position::default()
+        };
+
+        // 3 degrees before the trine and moving towards it
+        let m = aspect_between(&fast(217.), &fixed, &set).unwrap();
+        assert_eq!(m.aspect.kind, AspectKind::Trine);
+        assert_eq!(m.state, AspectState::Applying);
+
+        let m = aspect_between(&fast(223.), &fixed, &set).unwrap();
+        assert_eq!(m.state, AspectState::Separating);
+
+        // 100 - 345 wraps to a separation of 115
+        let m = aspect_between(&fast(345.), &fixed, &set).unwrap();
+        assert_eq!(m.aspect.kind, AspectKind::Trine);
+        assert_eq!(m.state, AspectState::Separating);
+    }
+
+    #[test]
+    #[should_panic(expected = "greater than zero")]
+    fn harmonic_zero_is_rejected() {
+        let zero = AspectKind::Harmonic {
+            harmonic: 0,
+            multiple: 1,
+        };
+        AspectSet::new().with(zero, 1.);
+    }
+
+    #[test]
+    fn full_moon_is_an_opposition() {
+        // full moon 2002-01-28 22:50 UT
+        let set = AspectSet::new().with(AspectKind::Opposition, 8.);
+        let events = find_aspects(
+            Body::Moon,
+            Body::Sun.into(),
+            &set,
+            2452275.5,
+            2452305.5,
+            0,
+            0.5,
+        )
+        .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert!((events[0].tjd_ut - 2452303.451).abs() < 0.01);
+        assert_eq!(events[0].before, AspectState::Applying);
+        assert_eq!(events[0].after, AspectState::Separating);
+    }
+}
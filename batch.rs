@@ -0,0 +1,169 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::Position;
+use crate::raw;
+use crate::types::*;
+use crate::util::*;
+use std::ffi::CStr;
+
+// Structure of arrays output of calc_ut_batch. Cell (time, body) is stored at
+// index(time, body) in every column so a full column can be handed to plotting or
+// table code without copying.
+#[derive(Debug, Clone, Default)]
+pub struct BatchOutput {
+    n_times: usize,
+    n_bodies: usize,
+    pub longitude: Vec<f64>,
+    pub latitude: Vec<f64>,
+    pub distance: Vec<f64>,
+    pub longitude_speed: Vec<f64>,
+    pub latitude_speed: Vec<f64>,
+    pub distance_speed: Vec<f64>,
+    // None when the cell was calculated successfully
+    pub errors: Vec<Option<SweError>>,
+}
+
+impl BatchOutput {
+    pub fn new(n_times: usize, n_bodies: usize) -> Self {
+        let mut out = BatchOutput::default();
+        out.resize(n_times, n_bodies);
+        out
+    }
+
+    // Keeps the allocations when the output is reused for a batch of the same or smaller size
+    pub fn resize(&mut self, n_times: usize, n_bodies: usize) {
+        let len = n_times * n_bodies;
+        self.n_times = n_times;
+        self.n_bodies = n_bodies;
+
+        for column in [
+            &mut self.longitude,
+            &mut self.latitude,
+            &mut self.distance,
+            &mut self.longitude_speed,
+            &mut self.latitude_speed,
+            &mut self.distance_speed,
+        ] {
+            column.clear();
+            column.resize(len, 0.);
+        }
+
+        self.errors.clear();
+        self.errors.resize(len, None);
+    }
+
+    pub fn n_times(&self) -> usize {
+        self.n_times
+    }
+
+    pub fn n_bodies(&self) -> usize {
+        self.n_bodies
+    }
+
+    pub fn index(&self, time: usize, body: usize) -> usize {
+        time * self.n_bodies + body
+    }
+
+    pub fn position(&self, time: usize, body: usize) -> Result<Position, &SweError> {
+        let i = self.index(time, body);
+        if let Some(err) = &self.errors[i] {
+            return Err(err);
+        }
+
+        Ok(Position {
+            longitude: self.longitude[i],
+            latitude: self.latitude[i],
+            distance: self.distance[i],
+            longitude_speed: self.longitude_speed[i],
+            latitude_speed: self.latitude_speed[i],
+            distance_speed: self.distance_speed[i],
+        })
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors.iter().filter(|e| e.is_some()).count()
+    }
+
+    fn set(&mut self, i: usize, xx: &[f64; 6]) {
+        self.longitude[i] = xx[0];
+        self.latitude[i] = xx[1];
+        self.distance[i] = xx[2];
+        self.longitude_speed[i] = xx[3];
+        self.latitude_speed[i] = xx[4];
+        self.distance_speed[i] = xx[5];
+    }
+}
+
+// Calculates every body at every time (UT) with swe_calc_ut. The output is resized to fit
+// and the xx and serr buffers are reused for all calls. Times are the outer loop since the
+// library caches nutation and the earth position per time.
+//
+// Returns the number of cells that failed; the errors are kept in out.errors.
+pub fn calc_ut_batch(times: &[f64], bodies: &[Body], iflag: i32, out: &mut BatchOutput) -> usize {
+    out.resize(times.len(), bodies.len());
+
+    let ipls: Vec<i32> = bodies.iter().map(|b| b.ipl()).collect();
+    let mut serr = new_max_buffer();
+    let mut xx: [f64; 6] = [0.; 6];
+    let mut failed = 0;
+
+    for (t, &tjd_ut) in times.iter().enumerate() {
+        for (b, &ipl) in ipls.iter().enumerate() {
+            let i = out.index(t, b);
+            serr[0] = 0;
+
+            let ret_code = unsafe {
+                raw::swe_calc_ut(tjd_ut, ipl, iflag, xx.as_mut_ptr(), serr.as_mut_ptr())
+            };
+
+            if ret_code < RAW_OK {
+                // only up to the nul, serr still holds the tail of longer earlier messages
+                let message = unsafe { CStr::from_ptr(serr.as_ptr()) };
+                let message = message.to_string_lossy().into_owned();
+                out.errors[i] = Some(SweError::from((ret_code, message)));
+                failed += 1;
+                continue;
+            }
+
+            out.set(i, &xx);
+        }
+    }
+
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::calc_ut;
+
+    #[test]
+    fn batch_matches_single_calls() {
+        let times = [2452275.5, 2452276.5, 2452277.5];
+        let bodies = [Body::Sun, Body::Moon, Body::Mars];
+        let iflag = raw::SEFLG_SPEED as i32;
+
+        let mut out = BatchOutput::default();
+        let failed = calc_ut_batch(&times, &bodies, iflag, &mut out);
+        assert_eq!(failed, 0);
+
+        for (t, &tjd_ut) in times.iter().enumerate() {
+            for (b, &body) in bodies.iter().enumerate() {
+                let single = calc_ut(body, tjd_ut, iflag).unwrap();
+                assert_eq!(out.position(t, b).unwrap(), single);
+            }
+        }
+    }
+
+    #[test]
+    fn batch_keeps_per_cell_errors() {
+        // no ephemeris file exists for this asteroid, the other cells still succeed
+        let bodies = [Body::Sun, Body::Asteroid(99_999_999)];
+        let mut out = BatchOutput::new(1, 2);
+        let failed = calc_ut_batch(&[2452275.5], &bodies, 0, &mut out);
+
+        assert_eq!(failed, 1);
+        assert!(out.position(0, 0).is_ok());
+        assert!(out.position(0, 1).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use libswisseph_sys::batch::{calc_ut_batch, BatchOutput};
+use libswisseph_sys::body::Body;
+use libswisseph_sys::tuple_result;
+
+// One year of daily positions for Sun to Pluto, per call vs batch
+fn bench_calc_ut(c: &mut Criterion) {
+    let times: Vec<f64> = (0..365).map(|d| 2451544.5 + d as f64).collect();
+    let bodies = Body::PLANETS;
+    let iflag = libswisseph_sys::SEFLG_SPEED as i32;
+
+    c.bench_function("swe_calc_ut per call", |b| {
+        b.iter(|| {
+            for &tjd_ut in &times {
+                for body in &bodies {
+                    let r = unsafe { tuple_result::swe_calc_ut(tjd_ut, body.ipl(), iflag) };
+                    black_box(r.unwrap());
+                }
+            }
+        })
+    });
+
+    let mut out = BatchOutput::new(times.len(), bodies.len());
+    c.bench_function("calc_ut_batch", |b| {
+        b.iter(|| black_box(calc_ut_batch(&times, &bodies, iflag, &mut out)))
+    });
+}
+
+criterion_group!(benches, bench_calc_ut);
+criterion_main!(benches);
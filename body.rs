@@ -0,0 +1,85 @@
+use crate::raw;
+use crate::tuple_result;
+
+// Bodies that can be passed to swe_calc/swe_calc_ut as ipl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Body {
+    Sun,
+    Moon,
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+    MeanNode,
+    TrueNode,
+    MeanApogee,
+    OscuApogee,
+    Earth,
+    Chiron,
+    Pholus,
+    Ceres,
+    Pallas,
+    Juno,
+    Vesta,
+    // Minor planet by catalogue number, e.g. Asteroid(433) for Eros. Needs the asteroid files.
+    Asteroid(i32),
+}
+
+impl Body {
+    // Sun to Pluto
+    pub const PLANETS: [Body; 10] = [
+        Body::Sun,
+        Body::Moon,
+        Body::Mercury,
+        Body::Venus,
+        Body::Mars,
+        Body::Jupiter,
+        Body::Saturn,
+        Body::Uranus,
+        Body::Neptune,
+        Body::Pluto,
+    ];
+
+    pub fn ipl(self) -> i32 {
+        let ipl = match self {
+            Body::Sun => raw::SE_SUN,
+            Body::Moon => raw::SE_MOON,
+            Body::Mercury => raw::SE_MERCURY,
+            Body::Venus => raw::SE_VENUS,
+            Body::Mars => raw::SE_MARS,
+            Body::Jupiter => raw::SE_JUPITER,
+            Body::Saturn => raw::SE_SATURN,
+            Body::Uranus => raw::SE_URANUS,
+            Body::Neptune => raw::SE_NEPTUNE,
+            Body::Pluto => raw::SE_PLUTO,
+            Body::MeanNode => raw::SE_MEAN_NODE,
+            Body::TrueNode => raw::SE_TRUE_NODE,
+            Body::MeanApogee => raw::SE_MEAN_APOG,
+            Body::OscuApogee => raw::SE_OSCU_APOG,
+            Body::Earth => raw::SE_EARTH,
+            Body::Chiron => raw::SE_CHIRON,
+            Body::Pholus => raw::SE_PHOLUS,
+            Body::Ceres => raw::SE_CERES,
+            Body::Pallas => raw::SE_PALLAS,
+            Body::Juno => raw::SE_JUNO,
+            Body::Vesta => raw::SE_VESTA,
+            Body::Asteroid(n) => return raw::SE_AST_OFFSET as i32 + n,
+        };
+
+        ipl as i32
+    }
+
+    pub fn name(self) -> String {
+        unsafe { tuple_result::swe_get_planet_name(self.ipl()) }
+    }
+}
+
+impl From<Body> for i32 {
+    fn from(body: Body) -> i32 {
+        body.ipl()
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::{calc, Position};
+use crate::raw;
+use crate::tuple_result;
+
+const MIN_DEGREE: usize = 12;
+const MAX_DEGREE: usize = 48;
+
+// Chebyshev approximation of one body over one segment. Longitude is unwrapped before
+// fitting so the series is continuous across 0/360.
+#[derive(Debug, Clone)]
+struct Segment {
+    start: f64,
+    coeffs: [Vec<f64>; 3],
+    derivs: [Vec<f64>; 3],
+}
+
+// Serves positions of bodies at nearby times from Chebyshev polynomials fitted to
+// swe_calc over fixed length segments. A segment is fitted on first use and checked
+// against direct calls between the fitting nodes; the degree is raised until the error is
+// below the tolerance. If that is not possible the segment is calculated directly.
+//
+// Speeds are the derivative of the polynomials and are returned whether or not
+// SEFLG_SPEED is in the flags.
+#[derive(Debug)]
+pub struct PositionCache {
+    iflag: i32,
+    segment_days: f64,
+    tolerance: f64,
+    generation: u64,
+    segments: HashMap<(Body, i64), Option<Segment>>,
+}
+
+impl PositionCache {
+    // Segments of 4 days and a tolerance of 1e-7 degrees (0.00036") by default
+    pub fn new(iflag: i32) -> Self {
+        PositionCache {
+            iflag: iflag & !(raw::SEFLG_SPEED as i32),
+            segment_days: 4.,
+            tolerance: 1e-7,
+            generation: tuple_result::generation(),
+            segments: HashMap::new(),
+        }
+    }
+
+    pub fn with_segment_days(mut self, segment_days: f64) -> Self {
+        assert!(segment_days > 0., "segment length must be greater than zero");
+        self.segment_days = segment_days;
+        self.invalidate();
+        self
+    }
+
+    // Maximum error of every coordinate in the units of the flags (degrees or radians, AU)
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        assert!(
+            tolerance.is_finite() && tolerance > 0.,
+            "tolerance must be finite and greater than zero"
+        );
+        self.tolerance = tolerance;
+        self.invalidate();
+        self
+    }
+
+    pub fn flags(&self) -> i32 {
+        self.iflag
+    }
+
+    pub fn set_flags(&mut self, iflag: i32) {
+        let iflag = iflag & !(raw::SEFLG_SPEED as i32);
+        if iflag != self.iflag {
+            self.iflag = iflag;
+            self.invalidate();
+        }
+    }
+
+    // Needed after changing the ephemeris, sidereal mode or topocentric location through
+    // raw or safe, the calls of tuple_result invalidate the cache themselves
+    pub fn invalidate(&mut self) {
+        self.segments.clear();
+        self.generation = tuple_result::generation();
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn position(&mut self, body: Body, tjd_et: f64) -> Result<Position, SweError> {
+        if self.generation != tuple_result::generation() {
+            self.invalidate();
+        }
+
+        let index = (tjd_et / self.segment_days).floor() as i64;
+        if !self.segments.contains_key(&(body, index)) {
+            let segment = self.fit(body, index as f64 * self.segment_days)?;
+            self.segments.insert((body, index), segment);
+        }
+
+        match &self.segments[&(body, index)] {
+            Some(segment) => Ok(self.evaluate(segment, tjd_et)),
+            None => calc(body, tjd_et, self.iflag | raw::SEFLG_SPEED as i32),
+        }
+    }
+
+    pub fn position_ut(&mut self, body: Body, tjd_ut: f64) -> Result<Position, SweError> {
+        let tjd_et = tjd_ut + unsafe { tuple_result::swe_deltat_ex(tjd_ut, self.iflag) };
+        self.position(body, tjd_et)
+    }
+
+    // Period longitude is unwrapped with, none for cartesian coordinates
+    fn period(&self) -> Option<f64> {
+        if self.iflag & raw::SEFLG_XYZ as i32 != 0 {
+            None
+        } else if self.iflag & raw::SEFLG_RADIANS as i32 != 0 {
+            Some(2. * PI)
+        } else {
+            Some(360.)
+        }
+    }
+
+    fn sample(&self, body: Body, tjd_et: f64) -> Result<[f64; 3], SweError> {
+        let p = calc(body, tjd_et, self.iflag)?;
+        Ok([p.longitude, p.latitude, p.distance])
+    }
+
+    fn fit(&self, body: Body, start: f64) -> Result<Option<Segment>, SweError> {
+        let half = self.segment_days / 2.;
+        let mid = start + half;
+
+        // checked at points that are never fitting nodes
+        let checks: Vec<(f64, [f64; 3])> = [-0.77, -0.31, 0.13, 0.58, 0.91]
+            .iter()
+            .map(|x| self.sample(body, mid + x * half).map(|v| (mid + x * half, v)))
+            .collect::<Result<_, _>>()?;
+
+        let mut degree = MIN_DEGREE;
+        while degree <= MAX_DEGREE {
+            let nodes: Vec<[f64; 3]> = (0..degree)
+                .map(|k| self.sample(body, mid + node(k, degree) * half))
+                .collect::<Result<_, _>>()?;
+
+            let mut coeffs: [Vec<f64>; 3] = Default::default();
+            for (i, c) in coeffs.iter_mut().enumerate() {
+                let mut values: Vec<f64> = nodes.iter().map(|v| v[i]).collect();
+                if i == 0 {
+                    if let Some(period) = self.period() {
+                        unwrap(&mut values, period);
+                    }
+                }
+                *c = chebyshev_fit(&values);
+            }
+
+            let derivs = [
+                chebyshev_derivative(&coeffs[0], half),
+                chebyshev_derivative(&coeffs[1], half),
+                chebyshev_derivative(&coeffs[2], half),
+            ];
+            let segment = Segment { start, coeffs, derivs };
+
+            let fits = checks.iter().all(|(t, expected)| {
+                let p = self.evaluate(&segment, *t);
+                let mut dlon = p.longitude - expected[0];
+                if let Some(period) = self.period() {
+                    dlon = (dlon + period / 2.).rem_euclid(period) - period / 2.;
+                }
+
+                dlon.abs() <= self.tolerance
+                    && (p.latitude - expected[1]).abs() <= self.tolerance
+                    && (p.distance - expected[2]).abs() <= self.tolerance
+            });
+
+            if fits {
+                return Ok(Some(segment));
+            }
+
+            degree += 12;
+        }
+
+        Ok(None)
+    }
+
+    fn evaluate(&self, segment: &Segment, tjd_et: f64) -> Position {
+        let half = self.segment_days / 2.;
+        let x = (tjd_et - segment.start - half) / half;
+
+        let mut longitude = chebyshev_eval(&segment.coeffs[0], x);
+        if let Some(period) = self.period() {
+            longitude = longitude.rem_euclid(period);
+        }
+
+        Position {
+            longitude,
+            latitude: chebyshev_eval(&segment.coeffs[1], x),
+            distance: chebyshev_eval(&segment.coeffs[2], x),
+            longitude_speed: chebyshev_eval(&segment.derivs[0], x),
+            latitude_speed: chebyshev_eval(&segment.derivs[1], x),
+            distance_speed: chebyshev_eval(&segment.derivs[2], x),
+        }
+    }
+}
+
+// k-th of n Chebyshev nodes on -1..1
+fn node(k: usize, n: usize) -> f64 {
+    (PI * (k as f64 + 0.5) / n as f64).cos()
+}
+
+fn unwrap(values: &mut [f64], period: f64) {
+    for i in 1..values.len() {
+        let d = values[i] - values[i - 1];
+        values[i] -= (d / period).round() * period;
+    }
+}
+
+// Coefficients from values at the Chebyshev nodes, c[0] already halved
+fn chebyshev_fit(values: &[f64]) -> Vec<f64> {
+    let n = values.len();
+    let mut c: Vec<f64> = (0..n)
+        .map(|j| {
+            let sum: f64 = values
+                .iter()
+                .enumerate()
+                .map(|(k, v)| v * (PI * j as f64 * (k as f64 + 0.5) / n as f64).cos())
+                .sum();
+            2. * sum / n as f64
+        })
+        .collect();
+    c[0] /= 2.;
+    c
+}
+
+// Coefficients of the derivative with respect to time, for a segment of 2 * half days
+fn chebyshev_derivative(c: &[f64], half: f64) -> Vec<f64> {
+    let n = c.len();
+    let mut d = vec![0.; n];
+    for j in (1..n).rev() {
+        let next = if j + 1 < n { d[j + 1] } else { 0. };
+        d[j - 1] = next + 2. * j as f64 * c[j];
+    }
+    d[0] /= 2.;
+    d.iter().map(|v| v / half).collect()
+}
+
+// Clenshaw recurrence
+fn chebyshev_eval(c: &[f64], x: f64) -> f64 {
+    let (mut b1, mut b2) = (0., 0.);
+    for &cj in c.iter().skip(1).rev() {
+        let b0 = 2. * x * b1 - b2 + cj;
+        b2 = b1;
+        b1 = b0;
+    }
+    x * b1 - b2 + c[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_matches_direct_calls() {
+        let iflag = raw::SEFLG_SPEED as i32;
+        let mut cache = PositionCache::new(iflag).with_tolerance(1e-6);
+
+        for i in 0..200 {
+            let tjd_et = 2452275.5 + i as f64 * 0.137;
+            for body in [Body::Moon, Body::Mercury, Body::Mars] {
+                let cached = cache.position(body, tjd_et).unwrap();
+                let direct = calc(body, tjd_et, iflag).unwrap();
+
+                let dlon = (cached.longitude - direct.longitude + 180.).rem_euclid(360.) - 180.;
+                assert!(dlon.abs() < 1e-6, "{:?} at {}: {}", body, tjd_et, dlon);
+                assert!((cached.latitude - direct.latitude).abs() < 1e-6);
+                assert!((cached.longitude_speed - direct.longitude_speed).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "tolerance must be finite")]
+    fn nan_tolerance_is_rejected() {
+        PositionCache::new(0).with_tolerance(f64::NAN);
+    }
+
+    #[test]
+    fn cache_is_invalidated() {
+        let mut cache = PositionCache::new(0);
+        cache.position(Body::Sun, 2452275.5).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        cache.set_flags(raw::SEFLG_EQUATORIAL as i32);
+        assert!(cache.is_empty());
+
+        let generation = cache.generation;
+        unsafe { tuple_result::swe_set_ephe_path("/users/ephe") };
+        cache.position(Body::Sun, 2452275.5).unwrap();
+        assert_ne!(cache.generation, generation);
+
+        let generation = cache.generation;
+        unsafe { tuple_result::swe_set_sid_mode(raw::SE_SIDM_LAHIRI as i32, 0., 0.) };
+        cache.position(Body::Sun, 2452275.5).unwrap();
+        assert_ne!(cache.generation, generation);
+    }
+}
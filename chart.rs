@@ -0,0 +1,248 @@
+use crate::aspect::{aspect_between, AspectMatch, AspectSet};
+use crate::body::Body;
+use crate::error::SweError;
+use crate::houses::{houses, HouseSystem, Houses};
+use crate::julian_day::{JulianDayUt, UtcDateTime};
+use crate::location::GeoLocation;
+use crate::position::{calc_ut, Position};
+use crate::raw;
+use crate::tuple_result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zodiac {
+    Tropical,
+    // Sidereal with one of the SE_SIDM_* modes, e.g. SE_SIDM_LAHIRI
+    Sidereal(i32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartOptions {
+    pub zodiac: Zodiac,
+    pub house_system: HouseSystem,
+    pub bodies: Vec<Body>,
+    // Passed to every swe_calc_ut and swe_houses_ex call. SEFLG_SPEED is always added,
+    // SEFLG_SIDEREAL follows zodiac.
+    pub iflag: i32,
+    pub aspects: AspectSet,
+}
+
+impl Default for ChartOptions {
+    // Tropical Placidus chart of Sun to Pluto and the true node with the major aspects
+    fn default() -> Self {
+        let mut bodies = Body::PLANETS.to_vec();
+        bodies.push(Body::TrueNode);
+
+        ChartOptions {
+            zodiac: Zodiac::Tropical,
+            house_system: HouseSystem::Placidus,
+            bodies,
+            iflag: raw::SEFLG_SWIEPH as i32,
+            aspects: AspectSet::major(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartBody {
+    pub body: Body,
+    pub position: Position,
+    // From swe_house_pos, the integer part is the house
+    pub house_position: f64,
+}
+
+impl ChartBody {
+    pub fn house(&self) -> usize {
+        self.house_position as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartAspect {
+    pub first: Body,
+    pub second: Body,
+    pub aspect: AspectMatch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub tjd_ut: f64,
+    pub location: GeoLocation,
+    pub zodiac: Zodiac,
+    // The flags actually used for every calculation of the chart
+    pub iflag: i32,
+    pub bodies: Vec<ChartBody>,
+    pub houses: Houses,
+    // One entry per pair of bodies in aspect, in the order of the body list
+    pub aspects: Vec<ChartAspect>,
+}
+
+// Sets the library's sidereal mode for a sidereal zodiac, and the topocentric location
+// when iflag has SEFLG_TOPOCTR. Both are per-thread state of the library that stays set.
+fn set_library_state(zodiac: Zodiac, location: &GeoLocation, iflag: i32) {
+    if let Zodiac::Sidereal(sid_mode) = zodiac {
+        unsafe { tuple_result::swe_set_sid_mode(sid_mode, 0., 0.) };
+    }
+    if iflag & raw::SEFLG_TOPOCTR as i32 != 0 {
+        unsafe {
+            tuple_result::swe_set_topo(location.longitude, location.latitude, location.altitude)
+        };
+    }
+}
+
+impl Chart {
+    // All positions and houses are calculated for tjd_ut with the same flags. A sidereal
+    // zodiac sets the library's sidereal mode, and SEFLG_TOPOCTR the topocentric location,
+    // which stay set for the thread.
+    pub fn new(
+        tjd_ut: f64,
+        location: GeoLocation,
+        options: &ChartOptions,
+    ) -> Result<Chart, SweError> {
+        let mut iflag = options.iflag | raw::SEFLG_SPEED as i32;
+        match options.zodiac {
+            Zodiac::Tropical => iflag &= !(raw::SEFLG_SIDEREAL as i32),
+            Zodiac::Sidereal(_) => iflag |= raw::SEFLG_SIDEREAL as i32,
+        }
+        set_library_state(options.zodiac, &location, iflag);
+
+        let houses = houses(tjd_ut, &location, options.house_system, iflag)?;
+        let positions = options
+            .bodies
+            .iter()
+            .map(|&body| Ok((body, calc_ut(body, tjd_ut, iflag)?)))
+            .collect::<Result<Vec<_>, SweError>>()?;
+
+        Chart::from_parts(
+            tjd_ut,
+            location,
+            options.zodiac,
+            iflag,
+            positions,
+            houses,
+            &options.aspects,
+        )
+    }
+
+    // Runs f with the sidereal mode and topocentric location of the chart, which charts
+    // built since may have changed, then sets the previous ones again
+    pub(crate) fn with_library_state<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = tuple_result::Settings::current();
+        set_library_state(self.zodiac, &self.location, self.iflag);
+        let result = f();
+        unsafe { previous.restore() };
+        result
+    }
+
+    // Chart from positions and houses calculated elsewhere, e.g. progressed or directed.
+    // Places the bodies in the houses and builds the aspect grid.
+    pub(crate) fn from_parts(
+        tjd_ut: f64,
+        location: GeoLocation,
+        zodiac: Zodiac,
+        iflag: i32,
+        positions: Vec<(Body, Position)>,
+        houses: Houses,
+        aspects: &AspectSet,
+    ) -> Result<Chart, SweError> {
+        let bodies = positions
+            .into_iter()
+            .map(|(body, position)| {
+                let house_position =
+                    houses.house_position(position.longitude, position.latitude)?;
+                Ok(ChartBody {
+                    body,
+                    position,
+                    house_position,
+                })
+            })
+            .collect::<Result<Vec<_>, SweError>>()?;
+
+        let grid = aspect_grid(&bodies, aspects);
+
+        Ok(Chart {
+            tjd_ut,
+            location,
+            zodiac,
+            iflag,
+            bodies,
+            houses,
+            aspects: grid,
+        })
+    }
+
+    // Chart for a UTC date and time
+    pub fn from_utc(
+        utc: UtcDateTime,
+        location: GeoLocation,
+        options: &ChartOptions,
+    ) -> Result<Chart, SweError> {
+        let JulianDayUt(tjd_ut) = JulianDayUt::try_from(utc)?;
+        Chart::new(tjd_ut, location, options)
+    }
+
+    pub fn body(&self, body: Body) -> Option<&ChartBody> {
+        self.bodies.iter().find(|b| b.body == body)
+    }
+
+    // Aspect between two bodies in either order
+    pub fn aspect(&self, a: Body, b: Body) -> Option<&ChartAspect> {
+        self.aspects
+            .iter()
+            .find(|x| (x.first == a && x.second == b) || (x.first == b && x.second == a))
+    }
+}
+
+pub(crate) fn aspect_grid(bodies: &[ChartBody], set: &AspectSet) -> Vec<ChartAspect> {
+    let mut aspects = Vec::new();
+    for (i, a) in bodies.iter().enumerate() {
+        for b in &bodies[i + 1..] {
+            if let Some(aspect) = aspect_between(&a.position, &b.position, set) {
+                aspects.push(ChartAspect {
+                    first: a.body,
+                    second: b.body,
+                    aspect,
+                });
+            }
+        }
+    }
+    aspects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aspect::AspectKind;
+
+    #[test]
+    fn full_moon_chart() {
+        // full moon 2002-01-28 22:50 UT
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let chart = Chart::new(2452303.451, zurich, &ChartOptions::default()).unwrap();
+
+        assert_eq!(chart.bodies.len(), 11);
+        assert_eq!(chart.houses.cusps.len(), 12);
+        assert!(chart.bodies.iter().all(|b| (1..=12).contains(&b.house())));
+
+        let opposition = chart.aspect(Body::Moon, Body::Sun).unwrap();
+        assert_eq!(opposition.aspect.aspect.kind, AspectKind::Opposition);
+        assert!(opposition.aspect.deviation.abs() < 0.1);
+    }
+
+    #[test]
+    fn sidereal_chart_is_shifted_by_ayanamsa() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let tropical = Chart::new(2452275.5, zurich, &ChartOptions::default()).unwrap();
+        let options = ChartOptions {
+            zodiac: Zodiac::Sidereal(raw::SE_SIDM_LAHIRI as i32),
+            ..ChartOptions::default()
+        };
+        let sidereal = Chart::new(2452275.5, zurich, &options).unwrap();
+
+        let sun_t = tropical.body(Body::Sun).unwrap();
+        let sun_s = sidereal.body(Body::Sun).unwrap();
+        let shift = (sun_t.position.longitude - sun_s.position.longitude).rem_euclid(360.);
+        assert!((shift - 23.9).abs() < 0.1);
+        // houses move with the zodiac, the house of a body does not change
+        assert_eq!(sun_t.house(), sun_s.house());
+    }
+}
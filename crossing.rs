@@ -0,0 +1,260 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::{calc_ut, Sign};
+use crate::raw;
+use crate::search::{next_event, EventSearch};
+use crate::solver::{longitude_minus, Solver};
+use crate::tuple_result;
+
+// Step and window for bodies without a native crossing function
+const STEP: f64 = 1.;
+const WINDOW: f64 = 365.25;
+// Long enough for Pluto to return to any longitude
+const MAX_SEARCH: f64 = 260. * 365.25;
+
+fn crossing_err((_, serr): (f64, String)) -> SweError {
+    SweError::from((raw::ERR, serr))
+}
+
+// Next time (UT) the Sun crosses longitude after tjd_ut, with swe_solcross_ut
+pub fn sun_crossing(longitude: f64, tjd_ut: f64, iflag: i32) -> Result<f64, SweError> {
+    unsafe { tuple_result::swe_solcross_ut(longitude, tjd_ut, iflag) }.map_err(crossing_err)
+}
+
+// Next time (UT) the Moon crosses longitude after tjd_ut, with swe_mooncross_ut
+pub fn moon_crossing(longitude: f64, tjd_ut: f64, iflag: i32) -> Result<f64, SweError> {
+    unsafe { tuple_result::swe_mooncross_ut(longitude, tjd_ut, iflag) }.map_err(crossing_err)
+}
+
+// The Moon crossing the ecliptic, at its ascending or descending node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeCrossing {
+    pub tjd_ut: f64,
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+// Next node crossing of the Moon after tjd_ut, with swe_mooncross_node_ut
+pub fn moon_node_crossing(tjd_ut: f64, iflag: i32) -> Result<NodeCrossing, SweError> {
+    let (jx, longitude, latitude) =
+        unsafe { tuple_result::swe_mooncross_node_ut(tjd_ut, iflag) }.map_err(crossing_err)?;
+
+    Ok(NodeCrossing { tjd_ut: jx, longitude, latitude })
+}
+
+// Next (or previous with backward) time (UT) the heliocentric longitude of body crosses
+// longitude, with swe_helio_cross_ut. Not available for the Sun, Moon, nodes and apsides.
+pub fn helio_crossing(
+    body: Body,
+    longitude: f64,
+    tjd_ut: f64,
+    iflag: i32,
+    backward: bool,
+) -> Result<f64, SweError> {
+    let dir = if backward { -1 } else { 1 };
+    let (_, jx) = unsafe {
+        tuple_result::swe_helio_cross_ut(body.ipl(), longitude, tjd_ut, iflag, dir)?
+    };
+
+    Ok(jx)
+}
+
+fn has_helio_crossing(body: Body) -> bool {
+    !matches!(
+        body,
+        Body::Sun
+            | Body::Moon
+            | Body::MeanNode
+            | Body::TrueNode
+            | Body::MeanApogee
+            | Body::OscuApogee
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Sun,
+    Moon,
+    Helio,
+    Solver,
+}
+
+fn method(body: Body, iflag: i32) -> Method {
+    let helio = iflag & raw::SEFLG_HELCTR as i32 != 0;
+    match body {
+        _ if helio && has_helio_crossing(body) => Method::Helio,
+        Body::Sun if !helio => Method::Sun,
+        Body::Moon if !helio => Method::Moon,
+        _ => Method::Solver,
+    }
+}
+
+// Next time (UT) body crosses longitude after tjd_ut in either direction. The library's
+// crossing functions are used for the Sun, Moon and heliocentric planets, the solver for
+// everything else.
+pub fn crossing(body: Body, longitude: f64, tjd_ut: f64, iflag: i32) -> Result<f64, SweError> {
+    match method(body, iflag) {
+        Method::Sun => sun_crossing(longitude, tjd_ut, iflag),
+        Method::Moon => moon_crossing(longitude, tjd_ut, iflag),
+        Method::Helio => helio_crossing(body, longitude, tjd_ut, iflag, false),
+        Method::Solver => {
+            let solver = Solver::new(STEP).angular(true);
+            let mut start = tjd_ut;
+            while start < tjd_ut + MAX_SEARCH {
+                let f = longitude_minus(body, iflag, longitude);
+                let roots = solver.roots(f, start, start + WINDOW)?;
+                if let Some(root) = roots.iter().find(|r| r.tjd > tjd_ut) {
+                    return Ok(root.tjd);
+                }
+                start += WINDOW;
+            }
+
+            Err(SweError::NotFound(format!(
+                "{:?} does not cross {} within {} days",
+                body, longitude, MAX_SEARCH
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ingress {
+    pub tjd_ut: f64,
+    // Sign entered
+    pub sign: Sign,
+    // Entered backwards, e.g. from Taurus into Aries
+    pub retrograde: bool,
+}
+
+// All sign ingresses of body between start and end (UT)
+pub fn ingresses(body: Body, start: f64, end: f64, iflag: i32) -> Ingresses {
+    Ingresses {
+        body,
+        iflag,
+        method: method(body, iflag),
+        tjd_ut: start,
+        end,
+        sign: None,
+    }
+}
+
+pub struct Ingresses {
+    body: Body,
+    iflag: i32,
+    method: Method,
+    tjd_ut: f64,
+    end: f64,
+    sign: Option<Sign>,
+}
+
+impl Ingresses {
+    fn sign_at(&self, tjd_ut: f64) -> Result<Sign, SweError> {
+        Ok(calc_ut(self.body, tjd_ut, self.iflag)?.sign())
+    }
+}
+
+impl EventSearch for Ingresses {
+    type Event = Ingress;
+    // about a second
+    const AFTER: f64 = 1e-5;
+
+    fn cursor(&mut self) -> &mut f64 {
+        &mut self.tjd_ut
+    }
+
+    fn end(&self) -> f64 {
+        self.end
+    }
+
+    fn search(&mut self) -> Result<Option<Ingress>, SweError> {
+        let sign = match self.sign {
+            Some(sign) => sign,
+            None => self.sign_at(self.tjd_ut)?,
+        };
+
+        let ingress = match self.method {
+            // these only move forward
+            Method::Sun | Method::Moon | Method::Helio => {
+                let next = sign.next();
+                let tjd_ut = crossing(self.body, next.start_longitude(), self.tjd_ut, self.iflag)?;
+                Ingress { tjd_ut, sign: next, retrograde: false }
+            }
+            Method::Solver => {
+                // step until the sign changes, then solve for the boundary crossed
+                let mut t0 = self.tjd_ut;
+                let (t1, entered) = loop {
+                    if t0 > self.end {
+                        return Ok(None);
+                    }
+                    let t1 = t0 + STEP;
+                    let s1 = self.sign_at(t1)?;
+                    if s1 != sign {
+                        break (t1, s1);
+                    }
+                    t0 = t1;
+                };
+
+                let retrograde = entered != sign.next();
+                let boundary = match retrograde {
+                    true => sign.start_longitude(),
+                    false => entered.start_longitude(),
+                };
+                let roots = Solver::new(t1 - t0)
+                    .angular(true)
+                    .roots(longitude_minus(self.body, self.iflag, boundary), t0, t1)?;
+                let tjd_ut = roots.first().map(|r| r.tjd).unwrap_or(t1);
+
+                Ingress { tjd_ut, sign: entered, retrograde }
+            }
+        };
+
+        if ingress.tjd_ut > self.end {
+            return Ok(None);
+        }
+
+        self.tjd_ut = ingress.tjd_ut + Self::AFTER;
+        self.sign = Some(ingress.sign);
+        Ok(Some(ingress))
+    }
+}
+
+impl Iterator for Ingresses {
+    type Item = Result<Ingress, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sun_ingresses_in_a_year() {
+        let all: Vec<Ingress> = ingresses(Body::Sun, 2452275.5, 2452640.5, 0)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(all.len(), 12);
+        assert_eq!(all[0].sign, Sign::Aquarius);
+        assert!(all.iter().all(|i| !i.retrograde));
+    }
+
+    #[test]
+    fn mercury_ingresses_use_solver() {
+        for ingress in ingresses(Body::Mercury, 2452275.5, 2452640.5, 0) {
+            let ingress = ingress.unwrap();
+            let p = calc_ut(Body::Mercury, ingress.tjd_ut, 0).unwrap();
+            let offset = (p.longitude + 15.).rem_euclid(30.) - 15.;
+            assert!(offset.abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn moon_node_crossing_is_on_the_ecliptic() {
+        let node = moon_node_crossing(2452275.5, 0).unwrap();
+        assert!(node.tjd_ut > 2452275.5 && node.tjd_ut < 2452275.5 + 14.);
+        assert!(node.latitude.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,350 @@
+use crate::error::SweError;
+use crate::position::Position;
+use crate::tuple_result;
+use crate::types::MAXCH;
+use std::fmt::Write as _;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Separator of the directories in the ephemeris path
+const PATH_SEPARATOR: char = if cfg!(windows) { ';' } else { ':' };
+
+// Tells apart the catalogue directories of one process
+static CATALOGUES: AtomicUsize = AtomicUsize::new(0);
+
+// A star in the ICRS as in the fixed star file (sefstars.txt) of the library
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogueStar {
+    pub name: String,
+    // Looked up with a leading comma, e.g. ",alTau"
+    pub nomenclature: String,
+    // Right ascension and declination in degrees
+    pub ra: f64,
+    pub dec: f64,
+    // Proper motion in milliarcseconds per year, in right ascension multiplied by cos(dec)
+    // as in the Hipparcos catalogue
+    pub pm_ra: f64,
+    pub pm_dec: f64,
+    // km/s, positive away from the Sun
+    pub radial_velocity: f64,
+    // Milliarcseconds
+    pub parallax: f64,
+    pub magnitude: f64,
+}
+
+impl CatalogueStar {
+    // Names the star file can not hold and numbers the library would read as 0
+    fn check(&self) -> Result<(), String> {
+        for (field, text) in [("name", &self.name), ("nomenclature", &self.nomenclature)] {
+            if let Some(c) = text
+                .chars()
+                .find(|&c| matches!(c, ',' | '#') || c.is_control())
+            {
+                return Err(format!("{} {:?} contains {:?}", field, text, c));
+            }
+        }
+
+        let numbers = [
+            ("ra", self.ra),
+            ("dec", self.dec),
+            ("pm_ra", self.pm_ra),
+            ("pm_dec", self.pm_dec),
+            ("radial_velocity", self.radial_velocity),
+            ("parallax", self.parallax),
+            ("magnitude", self.magnitude),
+        ];
+        match numbers.iter().find(|(_, x)| !x.is_finite()) {
+            Some((field, x)) => Err(format!("{} is {}", field, x)),
+            None => Ok(()),
+        }
+    }
+
+    // The line of the star file, with the coordinates in hours/degrees, minutes and seconds.
+    // Fails for names with commas, # or control characters and for numbers that are not
+    // finite.
+    pub fn to_line(&self) -> Result<String, String> {
+        self.check()?;
+
+        // rounded before splitting, so 59.99999s do not become 60s
+        let ra = (self.ra.rem_euclid(360.) / 15. * 36e6).round() as i64 % 864_000_000;
+        let dec = (self.dec.abs() * 36e5).round() as i64;
+        let sign = if self.dec < 0. { "-" } else { "+" };
+
+        let mut line = String::new();
+        let _ = write!(
+            line,
+            "{},{},ICRS,{:02},{:02},{:07.4},{}{:02},{:02},{:06.3},{},{},{},{},{}",
+            self.name,
+            self.nomenclature,
+            ra / 36_000_000,
+            ra / 600_000 % 60,
+            (ra % 600_000) as f64 / 1e4,
+            sign,
+            dec / 3_600_000,
+            dec / 60_000 % 60,
+            (dec % 60_000) as f64 / 1e3,
+            self.pm_ra,
+            self.pm_dec,
+            self.radial_velocity,
+            self.parallax,
+            self.magnitude,
+        );
+        Ok(line)
+    }
+
+    // Parses a line of the star file. Comments and empty lines give None. Only ICRS and
+    // J2000 entries can be read.
+    pub fn parse_line(line: &str) -> Result<Option<CatalogueStar>, String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() < 14 {
+            return Err(format!("{} fields, expected 14", fields.len()));
+        }
+        if fields[2] != "ICRS" && fields[2] != "2000" {
+            return Err(format!("equinox {} is not supported", fields[2]));
+        }
+
+        let number = |i: usize| -> Result<f64, String> {
+            fields[i]
+                .parse::<f64>()
+                .map_err(|_| format!("field {} is not a number: {}", i + 1, fields[i]))
+        };
+        // the sign of the declination is on the degrees, which can be -00
+        let dec_sign = if fields[6].starts_with('-') { -1. } else { 1. };
+
+        Ok(Some(CatalogueStar {
+            name: fields[0].to_string(),
+            nomenclature: fields[1].to_string(),
+            ra: (number(3)? + number(4)? / 60. + number(5)? / 3600.) * 15.,
+            dec: dec_sign * (number(6)?.abs() + number(7)? / 60. + number(8)? / 3600.),
+            pm_ra: number(9)?,
+            pm_dec: number(10)?,
+            radial_velocity: number(11)?,
+            parallax: number(12)?,
+            magnitude: number(13)?,
+        }))
+    }
+}
+
+// The contents of a star file with stars. The line of the error counts the header line.
+pub fn write_catalogue(stars: &[CatalogueStar]) -> Result<String, SweError> {
+    let mut out = String::from("# name,nomenclature,equinox,RA h,m,s,Dec d,m,s,");
+    out.push_str("pm RA mas/yr,pm Dec mas/yr,radial velocity km/s,parallax mas,magnitude\n");
+    for (i, star) in stars.iter().enumerate() {
+        let line = star
+            .to_line()
+            .map_err(|reason| SweError::InvalidCatalogue {
+                line: i + 2,
+                reason,
+            })?;
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+pub fn parse_catalogue(text: &str) -> Result<Vec<CatalogueStar>, SweError> {
+    let mut stars = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        match CatalogueStar::parse_line(line) {
+            Ok(Some(star)) => stars.push(star),
+            Ok(None) => {}
+            Err(reason) => {
+                return Err(SweError::InvalidCatalogue {
+                    line: i + 1,
+                    reason,
+                })
+            }
+        }
+    }
+    Ok(stars)
+}
+
+// A star file with custom stars in a temporary directory that is put in front of the
+// ephemeris path, so the library computes the stars like the ones of sefstars.txt. The
+// stars of the first sefstars.txt in ephe_path are copied after the custom ones and
+// stay available. The library keeps its state per thread (build.rs does not set
+// TLSOFF), so the catalogue is only seen by the thread that installed it, and it is not
+// Send so that it is dropped there too.
+pub struct CustomCatalogue {
+    dir: PathBuf,
+    ephe_path: String,
+    thread: PhantomData<*const ()>,
+}
+
+impl CustomCatalogue {
+    // Calls swe_close so the library reads the new star file. That resets the settings of
+    // the thread, e.g. the tidal acceleration or a user defined delta t; the sidereal mode
+    // and topocentric location set through tuple_result are set again.
+    pub fn install(stars: &[CatalogueStar], ephe_path: &str) -> Result<Self, SweError> {
+        let n = CATALOGUES.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("swisseph-stars-{}-{}", std::process::id(), n));
+        let path = format!("{}{}{}", dir.display(), PATH_SEPARATOR, ephe_path);
+        if path.len() >= MAXCH {
+            return Err(SweError::Io(format!(
+                "ephemeris path longer than {} bytes: {}",
+                MAXCH - 1,
+                path
+            )));
+        }
+
+        let mut contents = write_catalogue(stars)?;
+        let default = ephe_path
+            .split(PATH_SEPARATOR)
+            .map(|d| Path::new(d).join("sefstars.txt"))
+            .find(|f| f.is_file());
+        if let Some(file) = default {
+            contents.push_str(&fs::read_to_string(file)?);
+        }
+
+        fs::create_dir_all(&dir)?;
+        let catalogue = CustomCatalogue {
+            dir,
+            ephe_path: ephe_path.to_string(),
+            thread: PhantomData,
+        };
+        fs::write(catalogue.dir.join("sefstars.txt"), contents)?;
+        unsafe {
+            // drops the star data the library has already read
+            let settings = tuple_result::Settings::current();
+            tuple_result::swe_close();
+            tuple_result::swe_set_ephe_path(&path);
+            settings.restore();
+        }
+
+        Ok(catalogue)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Position of a star at tjd_ut (UT), by name or by ",nomenclature"
+    pub fn position_ut(&self, star: &str, tjd_ut: f64, iflag: i32) -> Result<Position, SweError> {
+        let (_, _, xx) = unsafe { tuple_result::swe_fixstar2_ut(star, tjd_ut, iflag)? };
+        Ok(Position::from(xx))
+    }
+
+    pub fn magnitude(&self, star: &str) -> Result<f64, SweError> {
+        let (_, _, magnitude) = unsafe { tuple_result::swe_fixstar2_mag(star)? };
+        Ok(magnitude)
+    }
+}
+
+// Calls swe_close like install and sets the ephe_path given to install, not the path
+// that was set before it
+impl Drop for CustomCatalogue {
+    fn drop(&mut self) {
+        unsafe {
+            let settings = tuple_result::Settings::current();
+            tuple_result::swe_close();
+            tuple_result::swe_set_ephe_path(&self.ephe_path);
+            settings.restore();
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw;
+    use crate::testing::{ephe_path, EPHE_PATH};
+
+    // Aldebaran from sefstars.txt
+    fn aldebaran(name: &str) -> CatalogueStar {
+        CatalogueStar {
+            name: name.to_string(),
+            nomenclature: "alTau".to_string(),
+            ra: (4. + 35. / 60. + 55.2390 / 3600.) * 15.,
+            dec: 16. + 30. / 60. + 33.485 / 3600.,
+            pm_ra: 63.45,
+            pm_dec: -188.94,
+            radial_velocity: 54.26,
+            parallax: 48.94,
+            magnitude: 0.86,
+        }
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let star = aldebaran("Aldebaran");
+        assert_eq!(
+            star.to_line().unwrap(),
+            "Aldebaran,alTau,ICRS,04,35,55.2390,+16,30,33.485,63.45,-188.94,54.26,48.94,0.86"
+        );
+
+        let south = CatalogueStar {
+            name: "Test".to_string(),
+            nomenclature: "xxTst".to_string(),
+            ra: 359.99999999,
+            dec: -0.5,
+            ..star.clone()
+        };
+        assert!(south
+            .to_line()
+            .unwrap()
+            .contains(",00,00,00.0000,-00,30,00.000,"));
+
+        let parsed = parse_catalogue(&write_catalogue(&[star.clone(), south]).unwrap()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!((parsed[0].ra - star.ra).abs() < 1e-6);
+        assert!((parsed[0].dec - star.dec).abs() < 1e-6);
+        assert_eq!(parsed[0].pm_dec, -188.94);
+        assert_eq!(parsed[1].dec, -0.5);
+
+        let err = parse_catalogue("# header\nAldebaran,alTau,1950,04").unwrap_err();
+        assert!(matches!(err, SweError::InvalidCatalogue { line: 2, .. }));
+    }
+
+    #[test]
+    fn invalid_stars_are_not_written() {
+        let comma = aldebaran("Alde,baran");
+        let newline = aldebaran("Alde\nbaran");
+        let nan = CatalogueStar {
+            parallax: f64::NAN,
+            ..aldebaran("Aldebaran")
+        };
+        assert!(comma.to_line().is_err());
+        assert!(newline.to_line().is_err());
+        assert!(nan.to_line().is_err());
+
+        let err = write_catalogue(&[aldebaran("Aldebaran"), nan]).unwrap_err();
+        assert!(matches!(err, SweError::InvalidCatalogue { line: 3, .. }));
+    }
+
+    #[test]
+    fn custom_star_matches_the_library() {
+        let _ephe = ephe_path();
+        let iflag = raw::SEFLG_SWIEPH as i32;
+        let tjd_ut = 2459580.5;
+        let (_, _, xx) =
+            unsafe { tuple_result::swe_fixstar2_ut("Aldebaran", tjd_ut, iflag) }.unwrap();
+
+        unsafe { tuple_result::swe_set_sid_mode(raw::SE_SIDM_LAHIRI as i32, 0., 0.) };
+        let ayanamsa = unsafe { tuple_result::swe_get_ayanamsa_ut(tjd_ut) };
+
+        let catalogue = CustomCatalogue::install(&[aldebaran("Rustbaran")], EPHE_PATH).unwrap();
+        // the sidereal mode is set again after swe_close
+        assert_eq!(
+            unsafe { tuple_result::swe_get_ayanamsa_ut(tjd_ut) },
+            ayanamsa
+        );
+        let custom = catalogue.position_ut("Rustbaran", tjd_ut, iflag).unwrap();
+        assert!((custom.longitude - xx[0]).abs() < 1e-6);
+        assert!((custom.latitude - xx[1]).abs() < 1e-6);
+        assert_eq!(catalogue.magnitude("Rustbaran").unwrap(), 0.86);
+        // the stars of the library are still there
+        assert!(catalogue.position_ut("Regulus", tjd_ut, iflag).is_ok());
+
+        let dir = catalogue.dir().to_path_buf();
+        drop(catalogue);
+        assert!(!dir.exists());
+    }
+}
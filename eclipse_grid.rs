@@ -0,0 +1,335 @@
+use crate::eclipses::{Eclipse, EclipseKind};
+use crate::error::SweError;
+use crate::tuple_result;
+use std::collections::HashMap;
+
+// A local eclipse maximum further than this from greatest eclipse belongs to another
+// eclipse
+const SAME_ECLIPSE: f64 = 0.5;
+
+// Corners and spacing of a grid in degrees. Longitudes are positive east.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub step: f64,
+}
+
+impl Region {
+    pub fn columns(&self) -> usize {
+        ((self.max_longitude - self.min_longitude) / self.step).round() as usize + 1
+    }
+
+    pub fn rows(&self) -> usize {
+        ((self.max_latitude - self.min_latitude) / self.step).round() as usize + 1
+    }
+
+    // A positive step and corners in order, so rows and columns are meaningful
+    fn check(&self) -> Result<(), SweError> {
+        let corners = [
+            self.min_longitude,
+            self.max_longitude,
+            self.min_latitude,
+            self.max_latitude,
+        ];
+        let valid = self.step.is_finite()
+            && self.step > 0.
+            && corners.iter().all(|x| x.is_finite())
+            && self.min_longitude <= self.max_longitude
+            && self.min_latitude <= self.max_latitude;
+        match valid {
+            true => Ok(()),
+            false => Err(SweError::InvalidArgument(format!(
+                "invalid region: {:?}",
+                self
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridCell {
+    pub longitude: f64,
+    pub latitude: f64,
+    // Local maximum of the eclipse (UT), None if it is not seen from here
+    pub tjd_max: Option<f64>,
+    // Fraction of the Sun's diameter covered at the local maximum
+    pub magnitude: f64,
+    // Fraction of the Sun's disc covered at the local maximum
+    pub obscuration: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridValue {
+    Magnitude,
+    Obscuration,
+}
+
+impl GridCell {
+    pub fn value(&self, value: GridValue) -> f64 {
+        match value {
+            GridValue::Magnitude => self.magnitude,
+            GridValue::Obscuration => self.obscuration,
+        }
+    }
+}
+
+// Local circumstances of a solar eclipse at the points of a region, from
+// swe_sol_eclipse_when_loc. cells are row by row from the south west corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EclipseGrid {
+    pub region: Region,
+    pub cells: Vec<GridCell>,
+}
+
+pub fn eclipse_grid(
+    eclipse: &Eclipse,
+    region: &Region,
+    iflag: i32,
+) -> Result<EclipseGrid, SweError> {
+    if eclipse.kind != EclipseKind::Solar {
+        return Err(SweError::InvalidArgument(
+            "eclipse grids exist for solar eclipses only".to_string(),
+        ));
+    }
+    region.check()?;
+
+    let mut cells = Vec::with_capacity(region.rows() * region.columns());
+    for row in 0..region.rows() {
+        for column in 0..region.columns() {
+            let longitude = region.min_longitude + column as f64 * region.step;
+            let latitude = region.min_latitude + row as f64 * region.step;
+            let geopos = [longitude, latitude, 0.];
+
+            let (flags, tret, attr) = unsafe {
+                tuple_result::swe_sol_eclipse_when_loc(eclipse.tjd_begin - 0.1, iflag, geopos, 0)?
+            };
+            let seen = flags > 0 && (tret[0] - eclipse.tjd_max).abs() < SAME_ECLIPSE;
+
+            cells.push(GridCell {
+                longitude,
+                latitude,
+                tjd_max: if seen { Some(tret[0]) } else { None },
+                magnitude: if seen { attr[0] } else { 0. },
+                obscuration: if seen { attr[2] } else { 0. },
+            });
+        }
+    }
+
+    Ok(EclipseGrid {
+        region: *region,
+        cells,
+    })
+}
+
+// A point on a grid edge: row and column of its first corner and whether the edge runs
+// east (true) or north
+type EdgeKey = (usize, usize, bool);
+
+impl EclipseGrid {
+    pub fn cell(&self, row: usize, column: usize) -> &GridCell {
+        &self.cells[row * self.region.columns() + column]
+    }
+
+    fn corner(&self, value: GridValue, row: usize, column: usize) -> f64 {
+        self.cell(row, column).value(value)
+    }
+
+    // Where level is crossed on an edge, interpolated linearly
+    fn edge_point(&self, value: GridValue, level: f64, (row, column, east): EdgeKey) -> (f64, f64) {
+        let (row2, column2) = match east {
+            true => (row, column + 1),
+            false => (row + 1, column),
+        };
+        let (a, b) = (
+            self.corner(value, row, column),
+            self.corner(value, row2, column2),
+        );
+        let t = if a == b { 0.5 } else { (level - a) / (b - a) };
+        let (p, q) = (self.cell(row, column), self.cell(row2, column2));
+
+        (
+            p.longitude + t * (q.longitude - p.longitude),
+            p.latitude + t * (q.latitude - p.latitude),
+        )
+    }
+
+    // Isolines of value at level with marching squares, as lists of (longitude,
+    // latitude). Closed lines end with their first point.
+    pub fn contour(&self, value: GridValue, level: f64) -> Vec<Vec<(f64, f64)>> {
+        let mut neighbours: HashMap<EdgeKey, Vec<EdgeKey>> = HashMap::new();
+
+        for r in 0..self.region.rows().saturating_sub(1) {
+            for c in 0..self.region.columns().saturating_sub(1) {
+                let v = [
+                    self.corner(value, r, c),
+                    self.corner(value, r, c + 1),
+                    self.corner(value, r + 1, c + 1),
+                    self.corner(value, r + 1, c),
+                ];
+                let mut case = 0;
+                for (i, x) in v.iter().enumerate() {
+                    if *x >= level {
+                        case |= 1 << i;
+                    }
+                }
+                let center = v.iter().sum::<f64>() / 4. >= level;
+
+                let bottom = (r, c, true);
+                let top = (r + 1, c, true);
+                let left = (r, c, false);
+                let right = (r, c + 1, false);
+
+                let segments: &[(EdgeKey, EdgeKey)] = match case {
+                    1 | 14 => &[(left, bottom)],
+                    2 | 13 => &[(bottom, right)],
+                    3 | 12 => &[(left, right)],
+                    4 | 11 => &[(right, top)],
+                    6 | 9 => &[(bottom, top)],
+                    7 | 8 => &[(left, top)],
+                    // saddles, split by the value in the middle of the square
+                    5 if center => &[(left, top), (bottom, right)],
+                    5 => &[(left, bottom), (right, top)],
+                    10 if center => &[(left, bottom), (right, top)],
+                    10 => &[(left, top), (bottom, right)],
+                    _ => &[],
+                };
+                for &(a, b) in segments {
+                    neighbours.entry(a).or_default().push(b);
+                    neighbours.entry(b).or_default().push(a);
+                }
+            }
+        }
+
+        // open lines start at an edge of the grid, what is left are closed lines
+        let mut starts: Vec<EdgeKey> = neighbours
+            .iter()
+            .filter(|(_, n)| n.len() == 1)
+            .map(|(k, _)| *k)
+            .collect();
+        starts.sort_unstable();
+        let mut rest: Vec<EdgeKey> = neighbours.keys().copied().collect();
+        rest.sort_unstable();
+        starts.extend(rest);
+
+        let mut lines = Vec::new();
+        for start in starts {
+            if !neighbours.contains_key(&start) {
+                continue;
+            }
+
+            let mut keys = vec![start];
+            let mut current = start;
+            while let Some(next) = neighbours.get_mut(&current).and_then(|n| n.pop()) {
+                if let Some(n) = neighbours.get_mut(&next) {
+                    if let Some(i) = n.iter().position(|k| *k == current) {
+                        n.swap_remove(i);
+                    }
+                }
+                if neighbours.get(&current).is_some_and(|n| n.is_empty()) {
+                    neighbours.remove(&current);
+                }
+                keys.push(next);
+                current = next;
+            }
+            neighbours.remove(&current);
+
+            lines.push(
+                keys.into_iter()
+                    .map(|k| self.edge_point(value, level, k))
+                    .collect(),
+            );
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eclipses::{eclipses, EclipseType};
+
+    #[test]
+    fn grid_over_the_united_states_2017() {
+        let eclipse = eclipses(
+            EclipseKind::Solar,
+            &[EclipseType::Total],
+            2457935.5,
+            2458000.5,
+            0,
+        )
+        .next()
+        .unwrap()
+        .unwrap();
+        let region = Region {
+            min_longitude: -90.,
+            max_longitude: -85.,
+            min_latitude: 35.,
+            max_latitude: 40.,
+            step: 1.,
+        };
+        let grid = eclipse_grid(&eclipse, &region, 0).unwrap();
+        assert_eq!(grid.cells.len(), 36);
+
+        // (-88, 37) is inside the path of totality, (-85, 40) is not
+        let total = grid.cell(2, 2);
+        assert!(total.obscuration > 0.999);
+        let partial = grid.cell(5, 5);
+        assert!(partial.obscuration > 0.8 && partial.obscuration < 0.99);
+        assert!((partial.tjd_max.unwrap() - eclipse.tjd_max).abs() < 0.1);
+
+        for invalid in [
+            Region { step: 0., ..region },
+            Region {
+                step: f64::NAN,
+                ..region
+            },
+            Region {
+                max_latitude: 30.,
+                ..region
+            },
+        ] {
+            let err = eclipse_grid(&eclipse, &invalid, 0).unwrap_err();
+            assert!(matches!(err, SweError::InvalidArgument(_)));
+        }
+    }
+
+    #[test]
+    fn contour_around_a_peak() {
+        let region = Region {
+            min_longitude: 0.,
+            max_longitude: 10.,
+            min_latitude: 0.,
+            max_latitude: 10.,
+            step: 1.,
+        };
+        let mut cells = Vec::new();
+        for row in 0..region.rows() {
+            for column in 0..region.columns() {
+                let (x, y) = (column as f64, row as f64);
+                let d = ((x - 5.).powi(2) + (y - 5.).powi(2)).sqrt();
+                cells.push(GridCell {
+                    longitude: x,
+                    latitude: y,
+                    tjd_max: None,
+                    magnitude: 0.,
+                    obscuration: (1. - d / 10.).max(0.),
+                });
+            }
+        }
+        let grid = EclipseGrid { region, cells };
+
+        let lines = grid.contour(GridValue::Obscuration, 0.7);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.first(), line.last());
+        // a circle of radius 3 around (5, 5)
+        for (x, y) in line {
+            let d = ((x - 5.).powi(2) + (y - 5.).powi(2)).sqrt();
+            assert!((d - 3.).abs() < 0.2);
+        }
+    }
+}
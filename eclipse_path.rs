@@ -0,0 +1,333 @@
+use crate::body::Body;
+use crate::eclipses::{Eclipse, EclipseKind};
+use crate::error::SweError;
+use crate::phenomena::pheno_ut;
+use crate::tuple_result;
+use std::fmt::Write;
+
+// Limits are searched outwards from the central line in these steps, in degrees of arc,
+// then refined by bisection
+const UMBRA_SCAN: f64 = 0.25;
+const UMBRA_MAX: f64 = 30.;
+const PENUMBRA_SCAN: f64 = 2.;
+const PENUMBRA_MAX: f64 = 90.;
+const BISECTIONS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPoint {
+    pub tjd_ut: f64,
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+// The track of a solar eclipse on the Earth. The central line comes from
+// swe_sol_eclipse_where, the limits are found across the track at every step with
+// swe_sol_eclipse_how. Penumbral limits only exist where the edge of the penumbra is on
+// the sunlit side of the Earth; where it is not, the limit follows the sunrise/sunset
+// line or is missing for that time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EclipsePath {
+    pub central_line: Vec<PathPoint>,
+    pub northern_umbra: Vec<PathPoint>,
+    pub southern_umbra: Vec<PathPoint>,
+    pub northern_penumbra: Vec<PathPoint>,
+    pub southern_penumbra: Vec<PathPoint>,
+}
+
+// Point at distance degrees of arc from lon/lat in the direction bearing (degrees east
+// of north)
+fn destination(longitude: f64, latitude: f64, bearing: f64, distance: f64) -> (f64, f64) {
+    let (lon, lat) = (longitude.to_radians(), latitude.to_radians());
+    let (b, d) = (bearing.to_radians(), distance.to_radians());
+    let lat2 = (lat.sin() * d.cos() + lat.cos() * d.sin() * b.cos()).asin();
+    let lon2 = lon + (b.sin() * d.sin() * lat.cos()).atan2(d.cos() - lat.sin() * lat2.sin());
+    let lon2 = (lon2.to_degrees() + 180.).rem_euclid(360.) - 180.;
+    (lon2, lat2.to_degrees())
+}
+
+fn bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.1.to_radians(), to.1.to_radians());
+    let dlon = (to.0 - from.0).to_radians();
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees()
+}
+
+fn central_point(tjd_ut: f64, iflag: i32) -> Result<(f64, f64), SweError> {
+    let (_, geopos, _) = unsafe { tuple_result::swe_sol_eclipse_where(tjd_ut, iflag)? };
+    Ok((geopos[0], geopos[1]))
+}
+
+// Negative inside the shadow at the place and time. Umbra means the Moon's disc is
+// completely inside the Sun's or covers it, penumbra any overlap. Places where the Sun
+// is below the horizon count as outside.
+fn shadow(
+    tjd_ut: f64,
+    place: (f64, f64),
+    sun_radius: f64,
+    umbra: bool,
+    iflag: i32,
+) -> Result<f64, SweError> {
+    let geopos = [place.0, place.1, 0.];
+    let (_, attr) = unsafe { tuple_result::swe_sol_eclipse_how(tjd_ut, iflag, geopos)? };
+    if attr[5] < 0. {
+        return Ok(1.);
+    }
+
+    let (separation, moon_radius) = (attr[7], attr[1] * sun_radius);
+    Ok(match umbra {
+        true => separation - (moon_radius - sun_radius).abs(),
+        false => separation - (moon_radius + sun_radius),
+    })
+}
+
+// Limit of the shadow from center in direction bearing, None if it is not on the sunlit
+// Earth within max degrees
+#[allow(clippy::too_many_arguments)]
+fn limit(
+    tjd_ut: f64,
+    center: (f64, f64),
+    bearing: f64,
+    sun_radius: f64,
+    umbra: bool,
+    scan: f64,
+    max: f64,
+    iflag: i32,
+) -> Result<Option<PathPoint>, SweError> {
+    let g = |d: f64| {
+        let place = destination(center.0, center.1, bearing, d);
+        shadow(tjd_ut, place, sun_radius, umbra, iflag)
+    };
+
+    if g(0.)? >= 0. {
+        return Ok(None);
+    }
+
+    let mut inside = 0.;
+    let mut outside = scan;
+    while g(outside)? < 0. {
+        inside = outside;
+        outside += scan;
+        if outside > max {
+            return Ok(None);
+        }
+    }
+    for _ in 0..BISECTIONS {
+        let mid = (inside + outside) / 2.;
+        if g(mid)? < 0. {
+            inside = mid;
+        } else {
+            outside = mid;
+        }
+    }
+
+    let (longitude, latitude) = destination(center.0, center.1, bearing, inside);
+    Ok(Some(PathPoint {
+        tjd_ut,
+        longitude,
+        latitude,
+    }))
+}
+
+// Path of a solar eclipse from the catalog, sampled every step days (a minute is
+// 1. / 1440.)
+pub fn eclipse_path(eclipse: &Eclipse, step: f64, iflag: i32) -> Result<EclipsePath, SweError> {
+    if eclipse.kind != EclipseKind::Solar {
+        return Err(SweError::InvalidArgument(
+            "eclipse paths exist for solar eclipses only".to_string(),
+        ));
+    }
+    if !(step.is_finite() && step > 0.) {
+        return Err(SweError::InvalidArgument(format!(
+            "eclipse path step must be finite and greater than zero: {}",
+            step
+        )));
+    }
+
+    let (_, tret) =
+        unsafe { tuple_result::swe_sol_eclipse_when_glob(eclipse.tjd_max - 0.5, iflag, 0, 0)? };
+    // center line begin and end, 0 for non-central eclipses
+    let central = tret[6] != 0. && tret[7] != 0.;
+    let (start, end) = (tret[2], tret[3]);
+
+    let mut path = EclipsePath::default();
+    let n = ((end - start) / step).ceil() as usize;
+    for i in 0..=n {
+        let t = (start + i as f64 * step).min(end);
+        let center = central_point(t, iflag)?;
+        let in_central_phase = central && t >= tret[6] && t <= tret[7];
+
+        // direction of the track from the central points a moment before and after
+        let dt = step.min(1. / 1440.) / 2.;
+        let heading = bearing(central_point(t - dt, iflag)?, central_point(t + dt, iflag)?);
+        let (north, south) = match heading.to_radians().sin() >= 0. {
+            // moving east, north is to the left
+            true => (heading - 90., heading + 90.),
+            false => (heading + 90., heading - 90.),
+        };
+        let sun_radius = pheno_ut(Body::Sun, t, iflag)?.diameter / 2.;
+
+        if in_central_phase {
+            path.central_line.push(PathPoint {
+                tjd_ut: t,
+                longitude: center.0,
+                latitude: center.1,
+            });
+            let (u, s, m) = (true, UMBRA_SCAN, UMBRA_MAX);
+            path.northern_umbra
+                .extend(limit(t, center, north, sun_radius, u, s, m, iflag)?);
+            path.southern_umbra
+                .extend(limit(t, center, south, sun_radius, u, s, m, iflag)?);
+        }
+
+        let (u, s, m) = (false, PENUMBRA_SCAN, PENUMBRA_MAX);
+        path.northern_penumbra
+            .extend(limit(t, center, north, sun_radius, u, s, m, iflag)?);
+        path.southern_penumbra
+            .extend(limit(t, center, south, sun_radius, u, s, m, iflag)?);
+    }
+
+    Ok(path)
+}
+
+fn coordinates(out: &mut String, points: &[PathPoint]) {
+    out.push('[');
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "[{:.6},{:.6}]", p.longitude, p.latitude);
+    }
+    out.push(']');
+}
+
+fn feature(out: &mut String, name: &str, geometry: &str, coords: &str) {
+    if out.ends_with('}') {
+        out.push(',');
+    }
+    let _ = write!(
+        out,
+        r#"{{"type":"Feature","properties":{{"name":"{}"}},"geometry":{{"type":"{}","coordinates":{}}}}}"#,
+        name, geometry, coords
+    );
+}
+
+impl EclipsePath {
+    // GeoJSON FeatureCollection with a LineString for the central line and each limit
+    // and a Polygon for the path of totality or annularity. Empty lines are left out.
+    // Lines crossing the antimeridian are not split.
+    pub fn to_geojson(&self) -> String {
+        let mut out = String::from(r#"{"type":"FeatureCollection","features":["#);
+
+        let lines = [
+            ("central_line", &self.central_line),
+            ("northern_umbra_limit", &self.northern_umbra),
+            ("southern_umbra_limit", &self.southern_umbra),
+            ("northern_penumbra_limit", &self.northern_penumbra),
+            ("southern_penumbra_limit", &self.southern_penumbra),
+        ];
+        for (name, points) in lines {
+            if points.len() >= 2 {
+                let mut coords = String::new();
+                coordinates(&mut coords, points);
+                feature(&mut out, name, "LineString", &coords);
+            }
+        }
+
+        if self.northern_umbra.len() >= 2 && self.southern_umbra.len() >= 2 {
+            let mut ring: Vec<PathPoint> = self.northern_umbra.clone();
+            ring.extend(self.southern_umbra.iter().rev());
+            ring.push(self.northern_umbra[0]);
+
+            let mut coords = String::from("[");
+            coordinates(&mut coords, &ring);
+            coords.push(']');
+            feature(&mut out, "umbra", "Polygon", &coords);
+        }
+
+        out.push_str("]}");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eclipses::{eclipses, EclipseType};
+
+    #[test]
+    fn path_of_totality_2017() {
+        let eclipse = eclipses(
+            EclipseKind::Solar,
+            &[EclipseType::Total],
+            2457935.5,
+            2458000.5,
+            0,
+        )
+        .next()
+        .unwrap()
+        .unwrap();
+        let path = eclipse_path(&eclipse, 5. / 1440., 0).unwrap();
+
+        // greatest eclipse near Hopkinsville, Kentucky, on a path about 115 km wide
+        let max = path
+            .central_line
+            .iter()
+            .min_by(|a, b| {
+                let da = (a.tjd_ut - eclipse.tjd_max).abs();
+                da.total_cmp(&(b.tjd_ut - eclipse.tjd_max).abs())
+            })
+            .unwrap();
+        assert!((max.latitude - 36.97).abs() < 0.5);
+        assert!((max.longitude + 87.67).abs() < 1.5);
+
+        let n = path
+            .northern_umbra
+            .iter()
+            .find(|p| p.tjd_ut == max.tjd_ut)
+            .unwrap();
+        let s = path
+            .southern_umbra
+            .iter()
+            .find(|p| p.tjd_ut == max.tjd_ut)
+            .unwrap();
+        let width_km = (n.latitude - s.latitude)
+            .hypot((n.longitude - s.longitude) * max.latitude.to_radians().cos())
+            * 111.2;
+        assert!((width_km - 115.).abs() < 15.);
+
+        let lunar = Eclipse {
+            kind: EclipseKind::Lunar,
+            ..eclipse
+        };
+        let err = eclipse_path(&lunar, 5. / 1440., 0).unwrap_err();
+        assert!(matches!(err, SweError::InvalidArgument(_)));
+        for step in [0., -1., f64::NAN] {
+            let err = eclipse_path(&eclipse, step, 0).unwrap_err();
+            assert!(matches!(err, SweError::InvalidArgument(_)));
+        }
+    }
+
+    #[test]
+    fn geojson_output() {
+        let p = |longitude, latitude| PathPoint {
+            tjd_ut: 0.,
+            longitude,
+            latitude,
+        };
+        let path = EclipsePath {
+            central_line: vec![p(-10., 0.), p(10., 0.)],
+            northern_umbra: vec![p(-10., 1.), p(10., 1.)],
+            southern_umbra: vec![p(-10., -1.), p(10., -1.)],
+            ..EclipsePath::default()
+        };
+
+        let json = path.to_geojson();
+        assert!(json.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature""#));
+        assert!(json.contains(r#""coordinates":[[-10.000000,0.000000],[10.000000,0.000000]]"#));
+        assert!(json.contains(
+            r#""type":"Polygon","coordinates":[[[-10.000000,1.000000],[10.000000,1.000000],[10.000000,-1.000000],[-10.000000,-1.000000],[-10.000000,1.000000]]]"#
+        ));
+        assert_eq!(json.matches(r#""type":"Feature""#).count(), 4);
+    }
+}
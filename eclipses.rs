@@ -0,0 +1,334 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::raw;
+use crate::search::{next_event, EventSearch};
+use crate::tuple_result;
+
+const AU_KM: f64 = 149597870.7;
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EclipseKind {
+    Solar,
+    Lunar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EclipseType {
+    Total,
+    Annular,
+    // Annular-total, annular along part of the path and total along the rest
+    Hybrid,
+    Partial,
+    // Lunar only
+    Penumbral,
+}
+
+impl EclipseType {
+    // The SE_ECL_* bit for ifltype
+    pub fn flag(self) -> i32 {
+        let flag = match self {
+            EclipseType::Total => raw::SE_ECL_TOTAL,
+            EclipseType::Annular => raw::SE_ECL_ANNULAR,
+            EclipseType::Hybrid => raw::SE_ECL_ANNULAR_TOTAL,
+            EclipseType::Partial => raw::SE_ECL_PARTIAL,
+            EclipseType::Penumbral => raw::SE_ECL_PENUMBRAL,
+        };
+        flag as i32
+    }
+
+    // From the flags returned by swe_sol_eclipse_when_glob/swe_lun_eclipse_when
+    pub fn from_flags(flags: i32) -> Option<EclipseType> {
+        let has = |flag: u32| flags & flag as i32 != 0;
+        if has(raw::SE_ECL_ANNULAR_TOTAL) {
+            Some(EclipseType::Hybrid)
+        } else if has(raw::SE_ECL_TOTAL) {
+            Some(EclipseType::Total)
+        } else if has(raw::SE_ECL_ANNULAR) {
+            Some(EclipseType::Annular)
+        } else if has(raw::SE_ECL_PARTIAL) {
+            Some(EclipseType::Partial)
+        } else if has(raw::SE_ECL_PENUMBRAL) {
+            Some(EclipseType::Penumbral)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eclipse {
+    pub kind: EclipseKind,
+    pub eclipse_type: EclipseType,
+    // Solar eclipses whose shadow axis touches the Earth
+    pub central: bool,
+    // Greatest eclipse (UT)
+    pub tjd_max: f64,
+    // First and last contact anywhere on Earth, or with the penumbra (UT)
+    pub tjd_begin: f64,
+    pub tjd_end: f64,
+    // Solar: magnitude as NASA gives it, the fraction of the Sun's diameter covered at
+    // greatest eclipse, or the ratio of the diameters for total and annular eclipses.
+    // Lunar: umbral magnitude.
+    pub magnitude: f64,
+    // Lunar only, 0 for solar eclipses
+    pub penumbral_magnitude: f64,
+    // Closest distance of the shadow axis from the center of the Earth (solar) or of the
+    // Moon from the axis of the Earth's shadow (lunar), in Earth radii, positive north
+    pub gamma: f64,
+    pub saros_series: i32,
+    pub saros_member: i32,
+    // Solar: duration of totality or annularity on the central line at greatest eclipse.
+    // Lunar: duration of the total phase. None for other eclipses. In days.
+    pub totality: Option<f64>,
+}
+
+// Solar or lunar eclipses between start and end (UT), optionally only of some types. An
+// empty types list finds all eclipses of the kind.
+pub fn eclipses(
+    kind: EclipseKind,
+    types: &[EclipseType],
+    start: f64,
+    end: f64,
+    iflag: i32,
+) -> Eclipses {
+    let all = match kind {
+        EclipseKind::Solar => raw::SE_ECL_ALLTYPES_SOLAR as i32,
+        EclipseKind::Lunar => raw::SE_ECL_ALLTYPES_LUNAR as i32,
+    };
+    let ifltype = match types {
+        [] => all,
+        _ => types.iter().fold(0, |acc, t| acc | t.flag()) & all,
+    };
+
+    Eclipses {
+        kind,
+        ifltype,
+        iflag,
+        tjd_ut: start,
+        end,
+    }
+}
+
+pub struct Eclipses {
+    kind: EclipseKind,
+    ifltype: i32,
+    iflag: i32,
+    tjd_ut: f64,
+    end: f64,
+}
+
+fn geocentric_xyz(body: Body, tjd_ut: f64, iflag: i32) -> Result<[f64; 3], SweError> {
+    let iflag = iflag | (raw::SEFLG_EQUATORIAL | raw::SEFLG_XYZ) as i32;
+    let (_, xx) = unsafe { tuple_result::swe_calc_ut(tjd_ut, body.ipl(), iflag)? };
+    Ok([xx[0], xx[1], xx[2]])
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// Distance of the center of the Earth (solar) or the Moon (lunar) from the shadow axis
+// in Earth radii, signed by the equatorial north direction
+fn gamma(kind: EclipseKind, tjd_ut: f64, iflag: i32) -> Result<f64, SweError> {
+    let moon = geocentric_xyz(Body::Moon, tjd_ut, iflag)?;
+    let sun = geocentric_xyz(Body::Sun, tjd_ut, iflag)?;
+
+    // a point on the shadow axis and its direction away from the Sun
+    let (point, axis) = match kind {
+        EclipseKind::Solar => (moon, [moon[0] - sun[0], moon[1] - sun[1], moon[2] - sun[2]]),
+        EclipseKind::Lunar => ([0.; 3], [-sun[0], -sun[1], -sun[2]]),
+    };
+    let norm = dot(axis, axis).sqrt();
+    let axis = [axis[0] / norm, axis[1] / norm, axis[2] / norm];
+
+    // perpendicular from the axis to the Earth's center or to the Moon
+    let target = match kind {
+        EclipseKind::Solar => [0.; 3],
+        EclipseKind::Lunar => moon,
+    };
+    let rel = [
+        target[0] - point[0],
+        target[1] - point[1],
+        target[2] - point[2],
+    ];
+    let along = dot(rel, axis);
+    let perp = [
+        rel[0] - along * axis[0],
+        rel[1] - along * axis[1],
+        rel[2] - along * axis[2],
+    ];
+
+    let distance = dot(perp, perp).sqrt() * AU_KM / EARTH_RADIUS_KM;
+    // for solar eclipses perp points from the axis to the Earth, north of the Earth is
+    // south of the axis
+    let north = match kind {
+        EclipseKind::Solar => perp[2] < 0.,
+        EclipseKind::Lunar => perp[2] > 0.,
+    };
+    Ok(if north { distance } else { -distance })
+}
+
+fn solar_eclipse(flags: i32, tret: [f64; 10], iflag: i32) -> Result<Eclipse, SweError> {
+    let tjd_max = tret[0];
+    let (_, geopos, attr) = unsafe { tuple_result::swe_sol_eclipse_where(tjd_max, iflag)? };
+    let central = flags & raw::SE_ECL_CENTRAL as i32 != 0;
+    let eclipse_type = EclipseType::from_flags(flags).unwrap_or(EclipseType::Partial);
+
+    // second and third contact where greatest eclipse is seen
+    let totality = match (central, eclipse_type) {
+        (true, EclipseType::Total | EclipseType::Annular | EclipseType::Hybrid) => {
+            let location = [geopos[0], geopos[1], 0.];
+            let (_, local, _) = unsafe {
+                tuple_result::swe_sol_eclipse_when_loc(tjd_max - 0.5, iflag, location, 0)?
+            };
+            Some(local[3] - local[2])
+        }
+        _ => None,
+    };
+
+    Ok(Eclipse {
+        kind: EclipseKind::Solar,
+        eclipse_type,
+        central,
+        tjd_max,
+        tjd_begin: tret[2],
+        tjd_end: tret[3],
+        // attr[0] is the diameter fraction also for total and annular eclipses
+        magnitude: attr[8],
+        penumbral_magnitude: 0.,
+        gamma: gamma(EclipseKind::Solar, tjd_max, iflag)?,
+        saros_series: attr[9] as i32,
+        saros_member: attr[10] as i32,
+        totality,
+    })
+}
+
+fn lunar_eclipse(flags: i32, tret: [f64; 10], iflag: i32) -> Result<Eclipse, SweError> {
+    let tjd_max = tret[0];
+    let (_, attr) = unsafe { tuple_result::swe_lun_eclipse_how(tjd_max, iflag, [0.; 3])? };
+    let eclipse_type = EclipseType::from_flags(flags).unwrap_or(EclipseType::Penumbral);
+
+    let totality = match eclipse_type {
+        EclipseType::Total => Some(tret[5] - tret[4]),
+        _ => None,
+    };
+
+    Ok(Eclipse {
+        kind: EclipseKind::Lunar,
+        eclipse_type,
+        central: false,
+        tjd_max,
+        tjd_begin: tret[6],
+        tjd_end: tret[7],
+        magnitude: attr[0],
+        penumbral_magnitude: attr[1],
+        gamma: gamma(EclipseKind::Lunar, tjd_max, iflag)?,
+        saros_series: attr[9] as i32,
+        saros_member: attr[10] as i32,
+        totality,
+    })
+}
+
+impl EventSearch for Eclipses {
+    type Event = Eclipse;
+    const AFTER: f64 = 1.;
+
+    fn cursor(&mut self) -> &mut f64 {
+        &mut self.tjd_ut
+    }
+
+    fn end(&self) -> f64 {
+        self.end
+    }
+
+    fn search(&mut self) -> Result<Option<Eclipse>, SweError> {
+        // none of the types apply to eclipses of this kind
+        if self.ifltype == 0 {
+            return Ok(None);
+        }
+
+        let (flags, tret) = unsafe {
+            match self.kind {
+                EclipseKind::Solar => tuple_result::swe_sol_eclipse_when_glob(
+                    self.tjd_ut,
+                    self.iflag,
+                    self.ifltype,
+                    0,
+                )?,
+                EclipseKind::Lunar => {
+                    tuple_result::swe_lun_eclipse_when(self.tjd_ut, self.iflag, self.ifltype, 0)?
+                }
+            }
+        };
+
+        if tret[0] > self.end {
+            return Ok(None);
+        }
+        self.tjd_ut = tret[0] + Self::AFTER;
+
+        let eclipse = match self.kind {
+            EclipseKind::Solar => solar_eclipse(flags, tret, self.iflag)?,
+            EclipseKind::Lunar => lunar_eclipse(flags, tret, self.iflag)?,
+        };
+        Ok(Some(eclipse))
+    }
+}
+
+impl Iterator for Eclipses {
+    type Item = Result<Eclipse, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_solar_eclipse_2017() {
+        // 2017-08-21, greatest eclipse 18:25:31 UT, Saros 145 member 22, gamma 0.4367,
+        // magnitude 1.0306, 2m40s of totality
+        let found: Vec<Eclipse> = eclipses(
+            EclipseKind::Solar,
+            &[EclipseType::Total],
+            2457935.5,
+            2458000.5,
+            0,
+        )
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        assert_eq!(found.len(), 1);
+        let e = found[0];
+        assert!(e.central);
+        assert!((e.tjd_max - 2457987.2677).abs() < 0.001);
+        assert_eq!((e.saros_series, e.saros_member), (145, 22));
+        assert!((e.gamma - 0.4367).abs() < 0.005);
+        assert!((e.magnitude - 1.0306).abs() < 0.002);
+        assert!((e.totality.unwrap() * 86400. - 160.).abs() < 3.);
+    }
+
+    #[test]
+    fn total_lunar_eclipses_2018() {
+        // Jan 31 and Jul 27, the second Saros 129 member 38 with 103 minutes of totality
+        let found: Vec<Eclipse> = eclipses(
+            EclipseKind::Lunar,
+            &[EclipseType::Total],
+            2458119.5,
+            2458484.5,
+            0,
+        )
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        assert_eq!(found.len(), 2);
+        let e = found[1];
+        assert_eq!(e.eclipse_type, EclipseType::Total);
+        assert_eq!((e.saros_series, e.saros_member), (129, 38));
+        assert!((e.gamma - 0.1168).abs() < 0.005);
+        assert!((e.totality.unwrap() * 1440. - 103.).abs() < 1.);
+    }
+}
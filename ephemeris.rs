@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::{calc_ut, Position, Sign};
+use crate::raw;
+
+// Time series of positions of one body, in UT:
+//
+//     let days = ephemeris(Body::Mars)
+//         .from(2452275.5)
+//         .to(2452640.5)
+//         .step(Duration::from_secs(86400));
+//
+//     for point in days { ... }
+//
+// With adaptive(true) a point is inserted at the exact time of every sign ingress and
+// retrograde/direct station between two steps. Only the first event of each kind is
+// found per step, so the step should be shorter than the time between such events.
+pub fn ephemeris(body: Body) -> Ephemeris {
+    Ephemeris {
+        body,
+        start: 0.,
+        end: 0.,
+        step: 1.,
+        iflag: raw::SEFLG_SPEED as i32,
+        adaptive: false,
+        precision: 1. / 86400.,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EphemerisEvent {
+    Ingress(Sign),
+    StationRetrograde,
+    StationDirect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EphemerisPoint {
+    pub tjd_ut: f64,
+    pub position: Position,
+    // Set on points inserted by the adaptive mode
+    pub event: Option<EphemerisEvent>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ephemeris {
+    body: Body,
+    start: f64,
+    end: f64,
+    step: f64,
+    iflag: i32,
+    adaptive: bool,
+    precision: f64,
+}
+
+impl Ephemeris {
+    pub fn from(mut self, tjd_ut: f64) -> Self {
+        self.start = tjd_ut;
+        self
+    }
+
+    // Inclusive if the end falls on a step
+    pub fn to(mut self, tjd_ut: f64) -> Self {
+        self.end = tjd_ut;
+        self
+    }
+
+    pub fn step(mut self, step: Duration) -> Self {
+        assert!(!step.is_zero(), "ephemeris step must be greater than zero");
+        self.step = step.as_secs_f64() / 86400.;
+        self
+    }
+
+    // Flags passed to swe_calc_ut, SEFLG_SPEED by default
+    pub fn flags(mut self, iflag: i32) -> Self {
+        self.iflag = iflag;
+        self
+    }
+
+    pub fn adaptive(mut self, adaptive: bool) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    // How exactly event times are located in adaptive mode, one second by default. Below
+    // the resolution of a Julian day, about 40 microseconds, the resolution is used.
+    pub fn precision(mut self, precision: Duration) -> Self {
+        assert!(!precision.is_zero(), "ephemeris precision must be greater than zero");
+        self.precision = precision.as_secs_f64() / 86400.;
+        self
+    }
+}
+
+impl IntoIterator for Ephemeris {
+    type Item = Result<EphemerisPoint, SweError>;
+    type IntoIter = EphemerisIter;
+
+    fn into_iter(mut self) -> EphemerisIter {
+        // station detection needs the speed
+        if self.adaptive {
+            self.iflag |= raw::SEFLG_SPEED as i32;
+        }
+
+        EphemerisIter {
+            ephemeris: self,
+            index: 0,
+            prev: None,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+pub struct EphemerisIter {
+    ephemeris: Ephemeris,
+    index: u64,
+    prev: Option<EphemerisPoint>,
+    pending: VecDeque<EphemerisPoint>,
+}
+
+impl EphemerisIter {
+    fn point(&self, tjd_ut: f64) -> Result<EphemerisPoint, SweError> {
+        let e = &self.ephemeris;
+        let position = calc_ut(e.body, tjd_ut, e.iflag)?;
+
+        Ok(EphemerisPoint { tjd_ut, position, event: None })
+    }
+
+    // Bisects until the first point where same(point) is false is known within precision
+    fn refine<F>(
+        &self,
+        mut lo: EphemerisPoint,
+        mut hi: EphemerisPoint,
+        same: F,
+    ) -> Result<EphemerisPoint, SweError>
+    where
+        F: Fn(&EphemerisPoint) -> bool,
+    {
+        while hi.tjd_ut - lo.tjd_ut > self.ephemeris.precision {
+            let tjd_ut = (lo.tjd_ut + hi.tjd_ut) / 2.;
+            // no f64 between lo and hi, the interval can not shrink further
+            if tjd_ut <= lo.tjd_ut || tjd_ut >= hi.tjd_ut {
+                break;
+            }
+            let mid = self.point(tjd_ut)?;
+            if same(&mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Ok(hi)
+    }
+
+    fn events(
+        &self,
+        prev: EphemerisPoint,
+        cur: EphemerisPoint,
+    ) -> Result<Vec<EphemerisPoint>, SweError> {
+        let mut events = Vec::new();
+
+        let sign = prev.position.sign();
+        if cur.position.sign() != sign {
+            let mut p = self.refine(prev, cur, |p| p.position.sign() == sign)?;
+            p.event = Some(EphemerisEvent::Ingress(p.position.sign()));
+            events.push(p);
+        }
+
+        let retrograde = prev.position.is_retrograde();
+        if cur.position.is_retrograde() != retrograde {
+            let mut p = self.refine(prev, cur, |p| p.position.is_retrograde() == retrograde)?;
+            p.event = Some(match retrograde {
+                true => EphemerisEvent::StationDirect,
+                false => EphemerisEvent::StationRetrograde,
+            });
+            events.push(p);
+        }
+
+        events.sort_by(|a, b| a.tjd_ut.total_cmp(&b.tjd_ut));
+        Ok(events)
+    }
+
+    fn step(&mut self) -> Result<Option<EphemerisPoint>, SweError> {
+        let e = &self.ephemeris;
+        // multiply instead of adding up steps so long series don't drift
+        let tjd_ut = e.start + self.index as f64 * e.step;
+        if tjd_ut > e.end + 1e-9 {
+            return Ok(None);
+        }
+        self.index += 1;
+
+        let point = self.point(tjd_ut)?;
+        if self.ephemeris.adaptive {
+            if let Some(prev) = self.prev {
+                self.pending.extend(self.events(prev, point)?);
+            }
+        }
+        self.prev = Some(point);
+        self.pending.push_back(point);
+
+        Ok(self.pending.pop_front())
+    }
+}
+
+impl Iterator for EphemerisIter {
+    type Item = Result<EphemerisPoint, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(point) = self.pending.pop_front() {
+            return Some(Ok(point));
+        }
+
+        self.step().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(86400);
+
+    #[test]
+    fn fixed_step_includes_end() {
+        let points: Vec<_> = ephemeris(Body::Moon)
+            .from(2452275.5)
+            .to(2452285.5)
+            .step(DAY)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(points.len(), 11);
+        assert!(points.iter().all(|p| p.event.is_none()));
+    }
+
+    #[test]
+    fn adaptive_finds_sun_ingress_into_aries() {
+        // march equinox 2002-03-20 19:16 UT
+        let events: Vec<_> = ephemeris(Body::Sun)
+            .from(2452348.5)
+            .to(2452358.5)
+            .step(DAY)
+            .adaptive(true)
+            .into_iter()
+            .map(Result::unwrap)
+            .filter(|p| p.event.is_some())
+            .collect();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, Some(EphemerisEvent::Ingress(Sign::Aries)));
+        assert!((events[0].tjd_ut - 2452354.303).abs() < 0.01);
+        assert!(events[0].position.longitude < 0.001);
+
+        // finer than a Julian day can resolve
+        let fine: Vec<_> = ephemeris(Body::Sun)
+            .from(2452348.5)
+            .to(2452358.5)
+            .step(DAY)
+            .adaptive(true)
+            .precision(Duration::from_nanos(1))
+            .into_iter()
+            .map(Result::unwrap)
+            .filter(|p| p.event.is_some())
+            .collect();
+        assert!((fine[0].tjd_ut - events[0].tjd_ut).abs() < 1. / 86400.);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+// Error type for the typed wrappers. The tuple_result functions return (i32, String)
+// errors which convert into SweError::Library so they can be used with `?`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SweError {
+    // Return code and error string (serr) from the C library
+    Library(i32, String),
+    // A date returned by the library can not be represented by the target date/time type
+    DateOutOfRange {
+        year: i32,
+        month: i32,
+        day: i32,
+    },
+    // A search did not find the event within its range
+    NotFound(String),
+    // An argument the function can not work with, e.g. a lunar eclipse for a solar one
+    InvalidArgument(String),
+    // A line of a star catalogue file that can not be parsed or written, counting from 1
+    InvalidCatalogue {
+        line: usize,
+        reason: String,
+    },
+    // Reading or writing a file failed
+    Io(String),
+}
+
+impl From<(i32, String)> for SweError {
+    fn from((code, serr): (i32, String)) -> Self {
+        // serr comes from a fixed size buffer and is padded with nul characters
+        SweError::Library(code, serr.trim_end_matches('\0').to_string())
+    }
+}
+
+impl From<std::io::Error> for SweError {
+    fn from(err: std::io::Error) -> Self {
+        SweError::Io(err.to_string())
+    }
+}
+
+impl fmt::Display for SweError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweError::Library(code, serr) => write!(f, "swisseph error {}: {}", code, serr),
+            SweError::DateOutOfRange { year, month, day } => write!(
+                f,
+                "date {:04}-{:02}-{:02} is out of range for the target date type",
+                year, month, day
+            ),
+            SweError::NotFound(what) => write!(f, "not found: {}", what),
+            SweError::InvalidArgument(what) => write!(f, "invalid argument: {}", what),
+            SweError::InvalidCatalogue { line, reason } => {
+                write!(f, "star catalogue line {}: {}", line, reason)
+            }
+            SweError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SweError {}
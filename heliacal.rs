@@ -0,0 +1,675 @@
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::raw;
+use crate::tuple_result;
+
+// How clear the air is, datm[3] of the heliacal functions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Extinction {
+    // Estimated by the library from pressure, temperature and humidity
+    Computed,
+    // Total atmospheric extinction coefficient ktot, between 0 and 1
+    Coefficient(f64),
+    // Meteorological visibility range in km
+    VisibilityRange(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Atmosphere {
+    // Pressure in hPa, 0 to estimate it from the altitude of the location
+    pub pressure: f64,
+    // Temperature in degrees Celsius and relative humidity in percent
+    pub temperature: f64,
+    pub humidity: f64,
+    pub extinction: Extinction,
+}
+
+impl Default for Atmosphere {
+    // The standard atmosphere of the library's examples
+    fn default() -> Self {
+        Atmosphere {
+            pressure: 1013.25,
+            temperature: 15.,
+            humidity: 40.,
+            extinction: Extinction::Computed,
+        }
+    }
+}
+
+impl Atmosphere {
+    // The datm array the heliacal functions take
+    pub fn to_datm(&self) -> [f64; 4] {
+        let extinction = match self.extinction {
+            Extinction::Computed => 0.,
+            Extinction::Coefficient(ktot) => ktot,
+            Extinction::VisibilityRange(km) => km,
+        };
+        [self.pressure, self.temperature, self.humidity, extinction]
+    }
+}
+
+// A binocular or telescope the observer looks through
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Optics {
+    // Both eyes, false for monocular
+    pub binocular: bool,
+    pub magnification: f64,
+    // Aperture diameter in mm and transmission, 0..1
+    pub aperture: f64,
+    pub transmission: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    // Age in years
+    pub age: f64,
+    // Visual acuity, 1 for normal sight, more for better than normal
+    pub snellen_ratio: f64,
+    // None for the naked eye
+    pub optics: Option<Optics>,
+}
+
+impl Default for Observer {
+    // The 36 year old observer with normal sight the library assumes
+    fn default() -> Self {
+        Observer {
+            age: 36.,
+            snellen_ratio: 1.,
+            optics: None,
+        }
+    }
+}
+
+impl Observer {
+    // The dobs array the heliacal functions take
+    pub fn to_dobs(&self) -> [f64; 6] {
+        match self.optics {
+            Some(o) => [
+                self.age,
+                self.snellen_ratio,
+                if o.binocular { 1. } else { 0. },
+                o.magnification,
+                o.aperture,
+                o.transmission,
+            ],
+            None => [self.age, self.snellen_ratio, 0., 0., 0., 0.],
+        }
+    }
+
+    // helflag with SE_HELFLAG_OPTICAL_PARAMS if optics are used, without it the library
+    // ignores dobs[2..]
+    pub(crate) fn helflag(&self, helflag: i32) -> i32 {
+        match self.optics {
+            Some(_) => helflag | raw::SE_HELFLAG_OPTICAL_PARAMS as i32,
+            None => helflag,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeliacalEvent {
+    // Heliacal rising, first visible in the morning sky
+    MorningFirst,
+    // Heliacal setting, last visible in the evening sky
+    EveningLast,
+    // Inner planets and the Moon only
+    EveningFirst,
+    MorningLast,
+    // Outer planets and stars only, rising at sunset and setting at sunrise
+    AcronychalRising,
+    CosmicalSetting,
+}
+
+impl HeliacalEvent {
+    // The SE_* value for TypeEvent
+    pub fn code(self) -> i32 {
+        let code = match self {
+            HeliacalEvent::MorningFirst => raw::SE_MORNING_FIRST,
+            HeliacalEvent::EveningLast => raw::SE_EVENING_LAST,
+            HeliacalEvent::EveningFirst => raw::SE_EVENING_FIRST,
+            HeliacalEvent::MorningLast => raw::SE_MORNING_LAST,
+            HeliacalEvent::AcronychalRising => raw::SE_ACRONYCHAL_RISING,
+            HeliacalEvent::CosmicalSetting => raw::SE_COSMICAL_SETTING,
+        };
+        code as i32
+    }
+}
+
+// From dret of swe_heliacal_ut (UT)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeliacalTimes {
+    pub first_visible: f64,
+    pub optimum: f64,
+    pub last_visible: f64,
+}
+
+// darr of swe_heliacal_pheno_ut. Angles in degrees, times in UT.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HeliacalPhenomena {
+    // Topocentric altitude of the object, unrefracted and refracted, and geocentric
+    pub altitude: f64,
+    pub apparent_altitude: f64,
+    pub geocentric_altitude: f64,
+    pub azimuth: f64,
+    pub sun_altitude: f64,
+    pub sun_azimuth: f64,
+    // Actual topocentric and geocentric arcus visionis
+    pub topocentric_arcus_visionis: f64,
+    pub arcus_visionis: f64,
+    // Actual azimuth and longitude differences between object and Sun
+    pub azimuth_difference: f64,
+    pub longitude_difference: f64,
+    pub extinction: f64,
+    pub min_topocentric_arcus_visionis: f64,
+    // First, optimum and last time the object is visible according to VR, and the best
+    // time according to Yallop
+    pub first_visible: f64,
+    pub optimum: f64,
+    pub last_visible: f64,
+    pub best_yallop: f64,
+    pub moon_crescent_width: f64,
+    // Yallop's q-test value and criterion
+    pub yallop_q: f64,
+    pub yallop_criterion: f64,
+    pub parallax: f64,
+    pub magnitude: f64,
+    // Rise or set of object and Sun, and the object's minus the Sun's
+    pub rise_set: f64,
+    pub sun_rise_set: f64,
+    pub lag: f64,
+    // Duration of visibility in days
+    pub visibility_duration: f64,
+    pub moon_crescent_length: f64,
+    // darr[26] and darr[28], both called CVAact by the library
+    pub cva: f64,
+    pub cva_new: f64,
+    // Illuminated fraction in percent
+    pub illumination: f64,
+    // darr[29], MSk
+    pub msk: f64,
+}
+
+impl From<[f64; 50]> for HeliacalPhenomena {
+    fn from(darr: [f64; 50]) -> Self {
+        HeliacalPhenomena {
+            altitude: darr[0],
+            apparent_altitude: darr[1],
+            geocentric_altitude: darr[2],
+            azimuth: darr[3],
+            sun_altitude: darr[4],
+            sun_azimuth: darr[5],
+            topocentric_arcus_visionis: darr[6],
+            arcus_visionis: darr[7],
+            azimuth_difference: darr[8],
+            longitude_difference: darr[9],
+            extinction: darr[10],
+            min_topocentric_arcus_visionis: darr[11],
+            first_visible: darr[12],
+            optimum: darr[13],
+            last_visible: darr[14],
+            best_yallop: darr[15],
+            moon_crescent_width: darr[16],
+            yallop_q: darr[17],
+            yallop_criterion: darr[18],
+            parallax: darr[19],
+            magnitude: darr[20],
+            rise_set: darr[21],
+            sun_rise_set: darr[22],
+            lag: darr[23],
+            visibility_duration: darr[24],
+            moon_crescent_length: darr[25],
+            cva: darr[26],
+            illumination: darr[27],
+            cva_new: darr[28],
+            msk: darr[29],
+        }
+    }
+}
+
+// Names of planets and stars are at most SE_MAX_STNAME bytes, without nul characters
+fn check_object(object: &str) -> Result<(), SweError> {
+    if object.len() > raw::SE_MAX_STNAME as usize || object.contains('\0') {
+        return Err(SweError::InvalidArgument(format!(
+            "object name longer than {} bytes or with nul: {}",
+            raw::SE_MAX_STNAME,
+            object
+        )));
+    }
+    Ok(())
+}
+
+// Next heliacal event of object after tjd_ut (UT). object is a planet name as returned by
+// Body::name or a fixed star. helflag takes the ephemeris flag and SE_HELFLAG_* bits.
+pub fn heliacal_ut(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+    object: &str,
+    event: HeliacalEvent,
+    helflag: i32,
+) -> Result<HeliacalTimes, SweError> {
+    check_object(object)?;
+    let (_, dret) = unsafe {
+        tuple_result::swe_heliacal_ut(
+            tjd_ut,
+            location.to_geopos(),
+            atmosphere.to_datm(),
+            observer.to_dobs(),
+            object,
+            event.code(),
+            observer.helflag(helflag),
+        )?
+    };
+
+    Ok(HeliacalTimes {
+        first_visible: dret[0],
+        optimum: dret[1],
+        last_visible: dret[2],
+    })
+}
+
+// Circumstances of a heliacal event of object at tjd_ut (UT)
+pub fn heliacal_pheno_ut(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+    object: &str,
+    event: HeliacalEvent,
+    helflag: i32,
+) -> Result<HeliacalPhenomena, SweError> {
+    check_object(object)?;
+    let (_, darr) = unsafe {
+        tuple_result::swe_heliacal_pheno_ut(
+            tjd_ut,
+            location.to_geopos(),
+            atmosphere.to_datm(),
+            observer.to_dobs(),
+            object,
+            event.code(),
+            observer.helflag(helflag),
+        )?
+    };
+
+    Ok(HeliacalPhenomena::from(darr))
+}
+
+// Return code of swe_vis_limit_mag for an object below the horizon
+const BELOW_HORIZON: i32 = -2;
+
+// Which receptors of the eye the limiting magnitude is calculated for, from the return
+// code of swe_vis_limit_mag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VisionRegime {
+    // Daylight, cones
+    Photopic,
+    // Darkness, rods
+    Scotopic,
+    // Near the limit between the two
+    Mesopic,
+}
+
+impl VisionRegime {
+    fn from_code(code: i32) -> VisionRegime {
+        if code & 2 != 0 {
+            VisionRegime::Mesopic
+        } else if code & 1 != 0 {
+            VisionRegime::Scotopic
+        } else {
+            VisionRegime::Photopic
+        }
+    }
+}
+
+// dret of swe_vis_limit_mag, angles in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitingMagnitude {
+    // Faintest magnitude that can be seen at the object's place in the sky
+    pub limiting_magnitude: f64,
+    pub object_magnitude: f64,
+    pub object_altitude: f64,
+    pub object_azimuth: f64,
+    pub sun_altitude: f64,
+    pub sun_azimuth: f64,
+    pub moon_altitude: f64,
+    pub moon_azimuth: f64,
+    pub regime: VisionRegime,
+}
+
+impl LimitingMagnitude {
+    pub fn is_visible(&self) -> bool {
+        self.limiting_magnitude > self.object_magnitude
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    BelowHorizon,
+    AboveHorizon(LimitingMagnitude),
+}
+
+impl Visibility {
+    pub fn is_visible(&self) -> bool {
+        match self {
+            Visibility::BelowHorizon => false,
+            Visibility::AboveHorizon(limit) => limit.is_visible(),
+        }
+    }
+}
+
+// Limiting magnitude at the place of object in the sky at tjd_ut (UT), with the same
+// object names and helflag as heliacal_ut
+pub fn vis_limit_mag(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+    object: &str,
+    helflag: i32,
+) -> Result<Visibility, SweError> {
+    check_object(object)?;
+    let (ret, dret) = unsafe {
+        tuple_result::swe_vis_limit_mag(
+            tjd_ut,
+            location.to_geopos(),
+            atmosphere.to_datm(),
+            observer.to_dobs(),
+            object,
+            observer.helflag(helflag),
+        )?
+    };
+    if ret == BELOW_HORIZON {
+        return Ok(Visibility::BelowHorizon);
+    }
+
+    Ok(Visibility::AboveHorizon(LimitingMagnitude {
+        limiting_magnitude: dret[0],
+        object_altitude: dret[1],
+        object_azimuth: dret[2],
+        sun_altitude: dret[3],
+        sun_azimuth: dret[4],
+        moon_altitude: dret[5],
+        moon_azimuth: dret[6],
+        object_magnitude: dret[7],
+        regime: VisionRegime::from_code(ret),
+    }))
+}
+
+// Whether object can be seen with the naked eye or the observer's optics at tjd_ut (UT),
+// with the Swiss Ephemeris files
+pub fn is_visible(
+    object: &str,
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+) -> Result<bool, SweError> {
+    let helflag = raw::SEFLG_SWIEPH as i32;
+    Ok(vis_limit_mag(tjd_ut, location, atmosphere, observer, object, helflag)?.is_visible())
+}
+
+// Where the object, the Sun and the Moon are in the sky, in degrees, for the arcus
+// visionis functions. The Moon is ignored when it is below the horizon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyPositions {
+    pub object_magnitude: f64,
+    pub object_azimuth: f64,
+    pub object_altitude: f64,
+    pub sun_azimuth: f64,
+    pub moon_azimuth: f64,
+    pub moon_altitude: f64,
+}
+
+impl From<&LimitingMagnitude> for SkyPositions {
+    fn from(limit: &LimitingMagnitude) -> Self {
+        SkyPositions {
+            object_magnitude: limit.object_magnitude,
+            object_azimuth: limit.object_azimuth,
+            object_altitude: limit.object_altitude,
+            sun_azimuth: limit.sun_azimuth,
+            moon_azimuth: limit.moon_azimuth,
+            moon_altitude: limit.moon_altitude,
+        }
+    }
+}
+
+// dret of swe_heliacal_angle, in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeliacalAngle {
+    pub heliacal_angle: f64,
+    pub min_topocentric_arcus_visionis: f64,
+    pub sun_altitude: f64,
+}
+
+// Heliacal angle for an object of the magnitude and azimuths in sky at tjd_ut (UT). The
+// object's altitude in sky is not used.
+pub fn heliacal_angle(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+    sky: &SkyPositions,
+    helflag: i32,
+) -> Result<HeliacalAngle, SweError> {
+    let (_, dret) = unsafe {
+        tuple_result::swe_heliacal_angle(
+            tjd_ut,
+            location.to_geopos(),
+            atmosphere.to_datm(),
+            observer.to_dobs(),
+            observer.helflag(helflag),
+            sky.object_magnitude,
+            sky.object_azimuth,
+            sky.sun_azimuth,
+            sky.moon_azimuth,
+            sky.moon_altitude,
+        )?
+    };
+
+    Ok(HeliacalAngle {
+        heliacal_angle: dret[0],
+        min_topocentric_arcus_visionis: dret[1],
+        sun_altitude: dret[2],
+    })
+}
+
+// Smallest depression of the Sun below the object's altitude at which the object in sky
+// can be seen, in degrees
+pub fn topo_arcus_visionis(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    atmosphere: &Atmosphere,
+    observer: &Observer,
+    sky: &SkyPositions,
+    helflag: i32,
+) -> Result<f64, SweError> {
+    let (_, tav) = unsafe {
+        tuple_result::swe_topo_arcus_visionis(
+            tjd_ut,
+            location.to_geopos(),
+            atmosphere.to_datm(),
+            observer.to_dobs(),
+            observer.helflag(helflag),
+            sky.object_magnitude,
+            sky.object_azimuth,
+            sky.object_altitude,
+            sky.sun_azimuth,
+            sky.moon_azimuth,
+            sky.moon_altitude,
+        )?
+    };
+
+    Ok(tav)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ephe_path;
+
+    #[test]
+    fn observer_and_atmosphere_arrays() {
+        let atmosphere = Atmosphere {
+            extinction: Extinction::VisibilityRange(40.),
+            ..Atmosphere::default()
+        };
+        assert_eq!(atmosphere.to_datm(), [1013.25, 15., 40., 40.]);
+
+        let naked = Observer::default();
+        assert_eq!(naked.to_dobs(), [36., 1., 0., 0., 0., 0.]);
+        assert_eq!(naked.helflag(0), 0);
+
+        let binoculars = Observer {
+            optics: Some(Optics {
+                binocular: true,
+                magnification: 7.,
+                aperture: 50.,
+                transmission: 0.8,
+            }),
+            ..naked
+        };
+        assert_eq!(binoculars.to_dobs(), [36., 1., 1., 7., 50., 0.8]);
+        assert_eq!(binoculars.helflag(0), raw::SE_HELFLAG_OPTICAL_PARAMS as i32);
+    }
+
+    #[test]
+    fn venus_morning_first_2022() {
+        // inferior conjunction 2022-01-09, Venus reappears in the morning a few days later
+        let location = GeoLocation::new(8.55, 47.37, 400.);
+        let (atmosphere, observer) = (Atmosphere::default(), Observer::default());
+        let times = heliacal_ut(
+            2459588.5,
+            &location,
+            &atmosphere,
+            &observer,
+            "venus",
+            HeliacalEvent::MorningFirst,
+            raw::SEFLG_SWIEPH as i32,
+        )
+        .unwrap();
+        assert!(times.first_visible > 2459589. && times.first_visible < 2459605.);
+
+        let pheno = heliacal_pheno_ut(
+            times.first_visible,
+            &location,
+            &atmosphere,
+            &observer,
+            "venus",
+            HeliacalEvent::MorningFirst,
+            raw::SEFLG_SWIEPH as i32,
+        )
+        .unwrap();
+        assert!(pheno.magnitude < -3.);
+        assert!(pheno.sun_altitude < 0.);
+    }
+
+    #[test]
+    fn jupiter_by_night_and_by_day() {
+        // 2022-09-26, Jupiter at opposition, seen from Zurich at local midnight and noon
+        let location = GeoLocation::new(8.55, 47.37, 400.);
+        let (atmosphere, observer) = (Atmosphere::default(), Observer::default());
+
+        let night = vis_limit_mag(
+            2459848.42,
+            &location,
+            &atmosphere,
+            &observer,
+            "jupiter",
+            raw::SEFLG_SWIEPH as i32,
+        )
+        .unwrap();
+        match night {
+            Visibility::AboveHorizon(limit) => {
+                assert_eq!(limit.regime, VisionRegime::Scotopic);
+                assert!(limit.object_magnitude < -2.5);
+                assert!(limit.sun_altitude < -18.);
+            }
+            Visibility::BelowHorizon => panic!("Jupiter is up at midnight"),
+        }
+        assert!(night.is_visible());
+
+        let noon = is_visible("jupiter", 2459848.92, &location, &atmosphere, &observer);
+        assert!(!noon.unwrap());
+
+        let long = "a".repeat(300);
+        let err = is_visible(&long, 2459848.42, &location, &atmosphere, &observer).unwrap_err();
+        assert!(matches!(err, SweError::InvalidArgument(_)));
+    }
+
+    fn sky_of(object: &str, tjd_ut: f64, location: &GeoLocation) -> SkyPositions {
+        let visibility = vis_limit_mag(
+            tjd_ut,
+            location,
+            &Atmosphere::default(),
+            &Observer::default(),
+            object,
+            raw::SEFLG_SWIEPH as i32,
+        )
+        .unwrap();
+        match visibility {
+            Visibility::AboveHorizon(limit) => SkyPositions::from(&limit),
+            Visibility::BelowHorizon => panic!("{} is below the horizon", object),
+        }
+    }
+
+    #[test]
+    fn heliacal_angle_of_venus() {
+        // 2022-01-20 6:29 UT in the morning twilight, 11 days after inferior conjunction
+        let location = GeoLocation::new(8.55, 47.37, 400.);
+        let sky = sky_of("venus", 2459599.77, &location);
+        let (atmosphere, observer) = (Atmosphere::default(), Observer::default());
+
+        let angle = heliacal_angle(
+            2459599.77,
+            &location,
+            &atmosphere,
+            &observer,
+            &sky,
+            raw::SEFLG_SWIEPH as i32,
+        )
+        .unwrap();
+        assert!(angle.min_topocentric_arcus_visionis > 0.);
+        assert!(angle.min_topocentric_arcus_visionis < 10.);
+        assert!(angle.sun_altitude < 0.);
+    }
+
+    #[test]
+    fn arcus_visionis_of_sirius() {
+        // the star needs sefstars.txt
+        let _ephe = ephe_path();
+        // 2022-01-15, 21h UT
+        let location = GeoLocation::new(8.55, 47.37, 400.);
+        let sky = sky_of("sirius", 2459595.375, &location);
+        assert!(sky.object_magnitude < -1.);
+        let (atmosphere, observer) = (Atmosphere::default(), Observer::default());
+        let helflag = raw::SEFLG_SWIEPH as i32;
+
+        let sirius = topo_arcus_visionis(
+            2459595.375,
+            &location,
+            &atmosphere,
+            &observer,
+            &sky,
+            helflag,
+        )
+        .unwrap();
+        assert!(sirius > 0. && sirius < 15.);
+
+        // a fainter star at the same place needs a darker sky
+        let faint = SkyPositions {
+            object_magnitude: 3.,
+            ..sky
+        };
+        let fainter = topo_arcus_visionis(
+            2459595.375,
+            &location,
+            &atmosphere,
+            &observer,
+            &faint,
+            helflag,
+        )
+        .unwrap();
+        assert!(fainter > sirius);
+    }
+}
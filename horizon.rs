@@ -0,0 +1,219 @@
+use crate::almanac::{rise_trans, RiseTrans, RiseTransEvent, RiseTransOptions};
+use crate::body::Body;
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::position::calc_ut;
+use crate::solver::azalt_flag;
+use crate::tuple_result;
+
+// Rise and set are recalculated with the horizon altitude at the last azimuth until the
+// altitude changes less than TOLERANCE degrees
+const TOLERANCE: f64 = 1e-4;
+const MAX_ITERATIONS: usize = 30;
+
+// Altitude of the visible horizon in degrees by azimuth in degrees from north through
+// east, e.g. measured with a clinometer. Altitudes between the points are interpolated
+// linearly, wrapping around north.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonProfile {
+    points: Vec<(f64, f64)>,
+}
+
+impl HorizonProfile {
+    // points are (azimuth, altitude) pairs in any order
+    pub fn new(points: &[(f64, f64)]) -> Self {
+        let mut points: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(azimuth, altitude)| (azimuth.rem_euclid(360.), altitude))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        HorizonProfile { points }
+    }
+
+    pub fn flat(altitude: f64) -> Self {
+        HorizonProfile::new(&[(0., altitude)])
+    }
+
+    pub fn altitude_at(&self, azimuth: f64) -> f64 {
+        let azimuth = azimuth.rem_euclid(360.);
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0.,
+        };
+
+        // the neighbours of azimuth, across north if it is outside the points
+        let after = self.points.iter().position(|p| p.0 >= azimuth);
+        let (a, b) = match after {
+            Some(0) => ((last.0 - 360., last.1), first),
+            Some(i) => (self.points[i - 1], self.points[i]),
+            None => (last, (first.0 + 360., first.1)),
+        };
+        if b.0 == a.0 {
+            return b.1;
+        }
+        a.1 + (azimuth - a.0) / (b.0 - a.0) * (b.1 - a.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizonCrossing {
+    pub tjd_ut: f64,
+    // Azimuth from north through east and the altitude of the horizon there, in degrees
+    pub azimuth: f64,
+    pub horizon: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileRiseSet {
+    // None if the body stays above or below the profile
+    pub rise: Option<HorizonCrossing>,
+    pub set: Option<HorizonCrossing>,
+}
+
+// Azimuth from north of body at tjd_ut
+fn azimuth(
+    body: Body,
+    tjd_ut: f64,
+    location: &GeoLocation,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<f64, SweError> {
+    let p = calc_ut(body, tjd_ut, iflag)?;
+    let xaz = unsafe {
+        tuple_result::swe_azalt(
+            tjd_ut,
+            azalt_flag(iflag),
+            location.to_geopos(),
+            options.pressure,
+            options.temperature,
+            [p.longitude, p.latitude, p.distance],
+        )
+    };
+
+    // swe_azalt counts from south through west
+    Ok((xaz[0] + 180.).rem_euclid(360.))
+}
+
+// Next rise or set of body over profile after tjd_ut (UT). The crossing depends on the
+// horizon altitude at the azimuth where it happens, so swe_rise_trans_true_hor is
+// repeated with the altitude of the profile at the azimuth of the previous result. A
+// body that dips behind a peak and reappears crosses the profile more than once; only
+// the first crossing is found.
+pub fn rise_trans_profile(
+    body: Body,
+    tjd_ut: f64,
+    event: RiseTransEvent,
+    location: &GeoLocation,
+    profile: &HorizonProfile,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<Option<HorizonCrossing>, SweError> {
+    // start from the crossing of a flat horizon
+    let mut options = RiseTransOptions {
+        horizon: 0.,
+        twilight: None,
+        ..*options
+    };
+    let mut tjd = match rise_trans(body, tjd_ut, event, location, &options, iflag)? {
+        RiseTrans::At(tjd) => tjd,
+        _ => return Ok(None),
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let azimuth = azimuth(body, tjd, location, &options, iflag)?;
+        let horizon = profile.altitude_at(azimuth);
+        if (horizon - options.horizon).abs() < TOLERANCE {
+            return Ok(Some(HorizonCrossing {
+                tjd_ut: tjd,
+                azimuth,
+                horizon,
+            }));
+        }
+
+        options.horizon = horizon;
+        tjd = match rise_trans(body, tjd_ut, event, location, &options, iflag)? {
+            RiseTrans::At(tjd) => tjd,
+            _ => return Ok(None),
+        };
+    }
+
+    Err(SweError::NotFound(format!(
+        "{:?} of {:?} over the horizon profile does not converge after {}",
+        event, body, tjd_ut
+    )))
+}
+
+// Next rise and set of body over profile after tjd_ut (UT)
+pub fn rise_set_profile(
+    body: Body,
+    tjd_ut: f64,
+    location: &GeoLocation,
+    profile: &HorizonProfile,
+    options: &RiseTransOptions,
+    iflag: i32,
+) -> Result<ProfileRiseSet, SweError> {
+    let event = |event| rise_trans_profile(body, tjd_ut, event, location, profile, options, iflag);
+    Ok(ProfileRiseSet {
+        rise: event(RiseTransEvent::Rise)?,
+        set: event(RiseTransEvent::Set)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_interpolation() {
+        let profile = HorizonProfile::new(&[(90., 10.), (270., 2.), (350., 4.)]);
+
+        assert_eq!(profile.altitude_at(90.), 10.);
+        assert_eq!(profile.altitude_at(180.), 6.);
+        // between 350 and 90 across north
+        assert_eq!(profile.altitude_at(40.), 7.);
+        assert_eq!(profile.altitude_at(-10.), 4.);
+        assert_eq!(HorizonProfile::flat(1.5).altitude_at(123.), 1.5);
+    }
+
+    #[test]
+    fn sunrise_behind_a_ridge() {
+        // 2022-06-21 in Greenwich, the Sun rises in the north east
+        let greenwich = GeoLocation::new(0., 51.48, 0.);
+        let options = RiseTransOptions::default();
+        let flat = rise_set_profile(
+            Body::Sun,
+            2459751.5,
+            &greenwich,
+            &HorizonProfile::flat(0.),
+            &options,
+            0,
+        )
+        .unwrap();
+        let flat_rise = flat.rise.unwrap();
+        assert!(flat_rise.azimuth > 45. && flat_rise.azimuth < 55.);
+
+        // a ridge of 5 degrees in the east
+        let ridge = HorizonProfile::new(&[(0., 0.), (30., 5.), (120., 5.), (150., 0.)]);
+        let rise = rise_trans_profile(
+            Body::Sun,
+            2459751.5,
+            RiseTransEvent::Rise,
+            &greenwich,
+            &ridge,
+            &options,
+            0,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(rise.tjd_ut > flat_rise.tjd_ut + 0.02);
+        assert!(rise.azimuth > flat_rise.azimuth);
+        assert!((rise.horizon - ridge.altitude_at(rise.azimuth)).abs() < TOLERANCE);
+        // the set over the flat western horizon is unchanged
+        let set = rise_set_profile(Body::Sun, 2459751.5, &greenwich, &ridge, &options, 0)
+            .unwrap()
+            .set
+            .unwrap();
+        assert!((set.tjd_ut - flat.set.unwrap().tjd_ut).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::raw;
+use crate::tuple_result;
+use crate::types::RAW_OK;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HouseSystem {
+    Placidus,
+    Koch,
+    Porphyry,
+    Regiomontanus,
+    Campanus,
+    Equal,
+    WholeSign,
+    Alcabitius,
+    Morinus,
+    Topocentric,
+    Meridian,
+    Vehlow,
+    // 36 sectors instead of 12 houses
+    Gauquelin,
+    // Any other hsys letter the library knows
+    Other(char),
+}
+
+impl HouseSystem {
+    // The hsys letter
+    pub fn code(self) -> i32 {
+        let c = match self {
+            HouseSystem::Placidus => 'P',
+            HouseSystem::Koch => 'K',
+            HouseSystem::Porphyry => 'O',
+            HouseSystem::Regiomontanus => 'R',
+            HouseSystem::Campanus => 'C',
+            HouseSystem::Equal => 'E',
+            HouseSystem::WholeSign => 'W',
+            HouseSystem::Alcabitius => 'B',
+            HouseSystem::Morinus => 'M',
+            HouseSystem::Topocentric => 'T',
+            HouseSystem::Meridian => 'X',
+            HouseSystem::Vehlow => 'V',
+            HouseSystem::Gauquelin => 'G',
+            HouseSystem::Other(c) => c,
+        };
+        c as i32
+    }
+
+    pub fn name(self) -> String {
+        unsafe { tuple_result::swe_house_name(self.code()) }
+    }
+
+    // 12, or 36 for Gauquelin sectors
+    pub fn count(self) -> usize {
+        match self {
+            HouseSystem::Gauquelin => 36,
+            _ => 12,
+        }
+    }
+}
+
+// The points swe_houses returns in ascmc
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Angles {
+    pub ascendant: f64,
+    pub mc: f64,
+    // Sidereal time in degrees
+    pub armc: f64,
+    pub vertex: f64,
+    pub equatorial_ascendant: f64,
+    // Co-ascendant by W. Koch
+    pub co_ascendant_koch: f64,
+    // Co-ascendant by M. Munkasey
+    pub co_ascendant_munkasey: f64,
+    pub polar_ascendant: f64,
+}
+
+impl From<[f64; 10]> for Angles {
+    fn from(ascmc: [f64; 10]) -> Self {
+        Angles {
+            ascendant: ascmc[raw::SE_ASC as usize],
+            mc: ascmc[raw::SE_MC as usize],
+            armc: ascmc[raw::SE_ARMC as usize],
+            vertex: ascmc[raw::SE_VERTEX as usize],
+            equatorial_ascendant: ascmc[raw::SE_EQUASC as usize],
+            co_ascendant_koch: ascmc[raw::SE_COASC1 as usize],
+            co_ascendant_munkasey: ascmc[raw::SE_COASC2 as usize],
+            polar_ascendant: ascmc[raw::SE_POLASC as usize],
+        }
+    }
+}
+
+// House cusps and angles for one time and place, with what swe_house_pos needs to place
+// bodies in them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Houses {
+    pub system: HouseSystem,
+    // cusps[0] is the first house
+    pub cusps: Vec<f64>,
+    pub angles: Angles,
+    // True obliquity of the ecliptic
+    pub obliquity: f64,
+    pub latitude: f64,
+    // Added to sidereal longitudes before calling swe_house_pos, 0 for tropical houses
+    pub ayanamsa: f64,
+}
+
+// Where the library can not calculate a system, e.g. Placidus or Koch within the polar
+// circles, it returns an error with Porphyry cusps instead
+fn fallback(ret_code: i32, system: HouseSystem) -> HouseSystem {
+    if ret_code < RAW_OK {
+        HouseSystem::Porphyry
+    } else {
+        system
+    }
+}
+
+// Houses with swe_houses_ex. With SEFLG_SIDEREAL in iflag the sidereal mode must have
+// been set with swe_set_sid_mode. Systems that fail at the latitude fall back to
+// Porphyry, check system of the result.
+pub fn houses(
+    tjd_ut: f64,
+    location: &GeoLocation,
+    system: HouseSystem,
+    iflag: i32,
+) -> Result<Houses, SweError> {
+    let (ret_code, cusps, ascmc) = unsafe {
+        tuple_result::swe_houses_ex(
+            tjd_ut,
+            iflag,
+            location.latitude,
+            location.longitude,
+            system.code(),
+        )
+    };
+    let system = fallback(ret_code, system);
+
+    let (_, nut) = unsafe { tuple_result::swe_calc_ut(tjd_ut, raw::SE_ECL_NUT, 0)? };
+    // with the nutation of iflag, like the positions placed in the houses
+    let ayanamsa = match iflag & raw::SEFLG_SIDEREAL as i32 {
+        0 => 0.,
+        _ => unsafe { tuple_result::swe_get_ayanamsa_ex_ut(tjd_ut, iflag)?.1 },
+    };
+
+    Ok(Houses {
+        system,
+        cusps: cusps[1..=system.count()].to_vec(),
+        angles: Angles::from(ascmc),
+        obliquity: nut[0],
+        latitude: location.latitude,
+        ayanamsa,
+    })
+}
+
+// Houses with swe_houses_armc for a sidereal time instead of a date, e.g. a progressed or
+// directed MC. The library calculates tropical houses; ayanamsa is subtracted from cusps
+// and angles for sidereal ones and should be 0 otherwise. Falls back to Porphyry like
+// houses.
+pub fn houses_armc(
+    armc: f64,
+    latitude: f64,
+    obliquity: f64,
+    system: HouseSystem,
+    ayanamsa: f64,
+) -> Result<Houses, SweError> {
+    let (ret_code, cusps, mut ascmc) =
+        unsafe { tuple_result::swe_houses_armc(armc, latitude, obliquity, system.code()) };
+    let system = fallback(ret_code, system);
+
+    let sidereal = |x: f64| (x - ayanamsa).rem_euclid(360.);
+    for (i, x) in ascmc.iter_mut().enumerate().take(raw::SE_NASCMC as usize) {
+        if i != raw::SE_ARMC as usize {
+            *x = sidereal(*x);
+        }
+    }
+
+    Ok(Houses {
+        system,
+        cusps: cusps[1..=system.count()]
+            .iter()
+            .map(|&c| sidereal(c))
+            .collect(),
+        angles: Angles::from(ascmc),
+        obliquity,
+        latitude,
+        ayanamsa,
+    })
+}
+
+// Right ascension of the MC for an ecliptic longitude of the MC, both in degrees
+pub fn mc_to_armc(mc: f64, obliquity: f64) -> f64 {
+    let (mc, eps) = (mc.to_radians(), obliquity.to_radians());
+    (mc.sin() * eps.cos())
+        .atan2(mc.cos())
+        .to_degrees()
+        .rem_euclid(360.)
+}
+
+impl Houses {
+    // Cusp of house 1..=12 (or 36)
+    pub fn cusp(&self, house: usize) -> f64 {
+        self.cusps[house - 1]
+    }
+
+    // Position of a point with swe_house_pos, from 1.0 up to but not including 13.0 (37.0
+    // for Gauquelin sectors). The integer part is the house number.
+    pub fn house_position(&self, longitude: f64, latitude: f64) -> Result<f64, SweError> {
+        let xpin = [longitude + self.ayanamsa, latitude];
+        let pos = unsafe {
+            tuple_result::swe_house_pos(
+                self.angles.armc,
+                self.latitude,
+                self.obliquity,
+                self.system.code(),
+                xpin,
+            )
+        };
+
+        // swe_house_pos returns 0 on error
+        if pos < 1. {
+            return Err(SweError::Library(
+                raw::ERR,
+                format!("house position of {} failed", longitude),
+            ));
+        }
+
+        Ok(pos)
+    }
+
+    pub fn house(&self, longitude: f64, latitude: f64) -> Result<usize, SweError> {
+        Ok(self.house_position(longitude, latitude)? as usize)
+    }
+
+    // Position of an ecliptic longitude between the cusps, on the same scale as
+    // house_position. For houses that are not tied to a sidereal time, e.g. composite
+    // cusps.
+    pub fn cusp_position(&self, longitude: f64) -> f64 {
+        let n = self.cusps.len();
+        for i in 0..n {
+            let span = (self.cusps[(i + 1) % n] - self.cusps[i]).rem_euclid(360.);
+            let offset = (longitude - self.cusps[i]).rem_euclid(360.);
+            if offset < span {
+                return (i + 1) as f64 + offset / span;
+            }
+        }
+        // the cusps do not go round the circle in order
+        1.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascendant_starts_the_first_house() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let h = houses(2452275.5, &zurich, HouseSystem::Placidus, 0).unwrap();
+
+        assert_eq!(h.cusps.len(), 12);
+        assert!((h.cusp(1) - h.angles.ascendant).abs() < 1e-9);
+        assert!((h.cusp(10) - h.angles.mc).abs() < 1e-9);
+
+        let pos = h.house_position(h.angles.ascendant + 0.1, 0.).unwrap();
+        assert_eq!(pos as usize, 1);
+    }
+
+    #[test]
+    fn whole_sign_cusps() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let h = houses(2452275.5, &zurich, HouseSystem::WholeSign, 0).unwrap();
+
+        assert!(h.cusps.iter().all(|c| c.rem_euclid(30.) < 1e-9));
+    }
+
+    #[test]
+    fn houses_from_armc_match_houses_from_date() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let h = houses(2452275.5, &zurich, HouseSystem::Koch, 0).unwrap();
+        let armc = mc_to_armc(h.angles.mc, h.obliquity);
+        assert!((armc - h.angles.armc).abs() < 1e-6);
+
+        let a = houses_armc(armc, zurich.latitude, h.obliquity, HouseSystem::Koch, 0.).unwrap();
+        assert!((a.angles.ascendant - h.angles.ascendant).abs() < 1e-6);
+    }
+
+    #[test]
+    fn placidus_falls_back_to_porphyry_in_the_arctic() {
+        let longyearbyen = GeoLocation::new(15.63, 78.22, 0.);
+        let h = houses(2452275.5, &longyearbyen, HouseSystem::Placidus, 0).unwrap();
+        let porphyry = houses(2452275.5, &longyearbyen, HouseSystem::Porphyry, 0).unwrap();
+
+        assert_eq!(h.system, HouseSystem::Porphyry);
+        assert_eq!(h.cusps, porphyry.cusps);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::error::SweError;
+use crate::raw;
+use crate::tuple_result;
+
+// Julian day numbers in Universal Time (UT1) and Ephemeris Time (TT). Keeping them as
+// separate types prevents passing an ET day to an _ut function and vice versa.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct JulianDayUt(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct JulianDayEt(pub f64);
+
+// A broken down UTC date in the gregorian calendar, as used by swe_utc_to_jd.
+// second is 60.x during an inserted leap second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtcDateTime {
+    pub year: i32,
+    pub month: i32,
+    pub day: i32,
+    pub hour: i32,
+    pub minute: i32,
+    pub second: f64,
+}
+
+impl UtcDateTime {
+    // Converts a local time that is offset_hours ahead of UTC (e.g. 5.5 for India)
+    pub fn from_local(
+        year: i32,
+        month: i32,
+        day: i32,
+        hour: i32,
+        minute: i32,
+        second: f64,
+        offset_hours: f64,
+    ) -> UtcDateTime {
+        let (year, month, day, hour, minute, second) = unsafe {
+            tuple_result::swe_utc_time_zone(year, month, day, hour, minute, second, offset_hours)
+        };
+
+        UtcDateTime { year, month, day, hour, minute, second }
+    }
+
+    // Returns the Julian day in ET and UT1. Leap seconds are taken into account, so this
+    // fails for a second of 60 on a day that had no leap second.
+    pub fn to_julian_day(&self) -> Result<(JulianDayEt, JulianDayUt), SweError> {
+        let (_, dret) = unsafe {
+            tuple_result::swe_utc_to_jd(
+                self.year,
+                self.month,
+                self.day,
+                self.hour,
+                self.minute,
+                self.second,
+                raw::SE_GREG_CAL as i32,
+            )?
+        };
+
+        Ok((JulianDayEt(dret[0]), JulianDayUt(dret[1])))
+    }
+}
+
+impl From<JulianDayUt> for UtcDateTime {
+    fn from(jd: JulianDayUt) -> Self {
+        let (year, month, day, hour, minute, second) = unsafe {
+            tuple_result::swe_jdut1_to_utc(jd.0, raw::SE_GREG_CAL as i32)
+        };
+
+        UtcDateTime { year, month, day, hour, minute, second }
+    }
+}
+
+impl From<JulianDayEt> for UtcDateTime {
+    fn from(jd: JulianDayEt) -> Self {
+        let (year, month, day, hour, minute, second) = unsafe {
+            tuple_result::swe_jdet_to_utc(jd.0, raw::SE_GREG_CAL as i32)
+        };
+
+        UtcDateTime { year, month, day, hour, minute, second }
+    }
+}
+
+impl TryFrom<UtcDateTime> for JulianDayUt {
+    type Error = SweError;
+
+    fn try_from(utc: UtcDateTime) -> Result<Self, Self::Error> {
+        utc.to_julian_day().map(|(_, ut)| ut)
+    }
+}
+
+impl TryFrom<UtcDateTime> for JulianDayEt {
+    type Error = SweError;
+
+    fn try_from(utc: UtcDateTime) -> Result<Self, Self::Error> {
+        utc.to_julian_day().map(|(et, _)| et)
+    }
+}
+
+// Splits fractional seconds into whole seconds and nanoseconds, without rounding up
+// into the next second
+pub(crate) fn split_seconds(second: f64) -> (u32, u32) {
+    let whole = second.floor();
+    let nanos = ((second - whole) * 1e9).round().min(999_999_999.) as u32;
+
+    (whole as u32, nanos)
+}
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_julian_day_round_trip() {
+        let utc = UtcDateTime { year: 2002, month: 1, day: 1, hour: 12, minute: 0, second: 0. };
+        let (et, ut) = utc.to_julian_day().unwrap();
+
+        // UT1 stays within a second of UTC, ET is ahead by delta T (~64s in 2002)
+        assert!((ut.0 - 2452276.0).abs() < 1. / 86400.);
+        assert!(et.0 > ut.0);
+
+        let back = UtcDateTime::from(ut);
+        assert_eq!((back.year, back.month, back.day, back.hour), (2002, 1, 1, 12));
+    }
+
+    #[test]
+    fn invalid_leap_second_is_an_error() {
+        let utc = UtcDateTime { year: 2002, month: 1, day: 1, hour: 23, minute: 59, second: 60.5 };
+        assert!(matches!(utc.to_julian_day(), Err(SweError::Library(_, _))));
+    }
+}
@@ -0,0 +1,101 @@
+use ::chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike, Utc};
+
+use super::{split_seconds, JulianDayEt, JulianDayUt, UtcDateTime};
+use crate::error::SweError;
+
+impl From<DateTime<Utc>> for UtcDateTime {
+    fn from(dt: DateTime<Utc>) -> Self {
+        // chrono stores a leap second as second 59 with nanoseconds >= 1_000_000_000
+        let second = dt.second() as f64 + dt.nanosecond() as f64 / 1e9;
+
+        UtcDateTime {
+            year: dt.year(),
+            month: dt.month() as i32,
+            day: dt.day() as i32,
+            hour: dt.hour() as i32,
+            minute: dt.minute() as i32,
+            second,
+        }
+    }
+}
+
+impl TryFrom<UtcDateTime> for DateTime<Utc> {
+    type Error = SweError;
+
+    fn try_from(utc: UtcDateTime) -> Result<Self, Self::Error> {
+        let out_of_range = SweError::DateOutOfRange {
+            year: utc.year,
+            month: utc.month,
+            day: utc.day,
+        };
+
+        let (mut sec, mut nanos) = split_seconds(utc.second);
+        if sec >= 60 {
+            sec = 59;
+            nanos += 1_000_000_000;
+        }
+
+        let naive = NaiveDate::from_ymd_opt(utc.year, utc.month as u32, utc.day as u32)
+            .and_then(|d| d.and_hms_nano_opt(utc.hour as u32, utc.minute as u32, sec, nanos))
+            .ok_or(out_of_range)?;
+
+        Ok(Utc.from_utc_datetime(&naive))
+    }
+}
+
+impl TryFrom<DateTime<Utc>> for JulianDayUt {
+    type Error = SweError;
+
+    fn try_from(dt: DateTime<Utc>) -> Result<Self, Self::Error> {
+        UtcDateTime::from(dt).try_into()
+    }
+}
+
+impl TryFrom<DateTime<Utc>> for JulianDayEt {
+    type Error = SweError;
+
+    fn try_from(dt: DateTime<Utc>) -> Result<Self, Self::Error> {
+        UtcDateTime::from(dt).try_into()
+    }
+}
+
+impl TryFrom<JulianDayUt> for DateTime<Utc> {
+    type Error = SweError;
+
+    fn try_from(jd: JulianDayUt) -> Result<Self, Self::Error> {
+        UtcDateTime::from(jd).try_into()
+    }
+}
+
+impl TryFrom<JulianDayEt> for DateTime<Utc> {
+    type Error = SweError;
+
+    fn try_from(jd: JulianDayEt) -> Result<Self, Self::Error> {
+        UtcDateTime::from(jd).try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chrono_leap_second_round_trip() {
+        let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_500_000_000)
+            .unwrap();
+        let dt = Utc.from_utc_datetime(&leap);
+
+        let jd = JulianDayEt::try_from(dt).unwrap();
+        let back = DateTime::<Utc>::try_from(jd).unwrap();
+        assert_eq!(back.second(), 59);
+        assert!(back.nanosecond() >= 1_000_000_000);
+    }
+
+    #[test]
+    fn chrono_out_of_range() {
+        let err = DateTime::<Utc>::try_from(JulianDayUt(1.0e8)).unwrap_err();
+        assert!(matches!(err, SweError::DateOutOfRange { .. }));
+    }
+}
@@ -0,0 +1,116 @@
+use ::time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use super::{split_seconds, JulianDayEt, JulianDayUt, UtcDateTime};
+use crate::error::SweError;
+
+impl From<OffsetDateTime> for UtcDateTime {
+    fn from(dt: OffsetDateTime) -> Self {
+        let second = dt.second() as f64 + dt.nanosecond() as f64 / 1e9;
+        let offset = dt.offset();
+
+        if offset.is_utc() {
+            return UtcDateTime {
+                year: dt.year(),
+                month: dt.month() as i32,
+                day: dt.day() as i32,
+                hour: dt.hour() as i32,
+                minute: dt.minute() as i32,
+                second,
+            };
+        }
+
+        UtcDateTime::from_local(
+            dt.year(),
+            dt.month() as i32,
+            dt.day() as i32,
+            dt.hour() as i32,
+            dt.minute() as i32,
+            second,
+            offset.whole_seconds() as f64 / 3600.,
+        )
+    }
+}
+
+// The time crate has no leap seconds, so 23:59:60.x is clamped to the end of 23:59:59
+impl TryFrom<UtcDateTime> for OffsetDateTime {
+    type Error = SweError;
+
+    fn try_from(utc: UtcDateTime) -> Result<Self, Self::Error> {
+        let out_of_range = SweError::DateOutOfRange {
+            year: utc.year,
+            month: utc.month,
+            day: utc.day,
+        };
+
+        let (mut sec, mut nanos) = split_seconds(utc.second);
+        if sec >= 60 {
+            sec = 59;
+            nanos = 999_999_999;
+        }
+
+        let month = Month::try_from(utc.month as u8).map_err(|_| out_of_range.clone())?;
+        let date = Date::from_calendar_date(utc.year, month, utc.day as u8)
+            .map_err(|_| out_of_range.clone())?;
+        let time = Time::from_hms_nano(utc.hour as u8, utc.minute as u8, sec as u8, nanos)
+            .map_err(|_| out_of_range)?;
+
+        Ok(PrimitiveDateTime::new(date, time).assume_offset(UtcOffset::UTC))
+    }
+}
+
+impl TryFrom<OffsetDateTime> for JulianDayUt {
+    type Error = SweError;
+
+    fn try_from(dt: OffsetDateTime) -> Result<Self, Self::Error> {
+        UtcDateTime::from(dt).try_into()
+    }
+}
+
+impl TryFrom<OffsetDateTime> for JulianDayEt {
+    type Error = SweError;
+
+    fn try_from(dt: OffsetDateTime) -> Result<Self, Self::Error> {
+        UtcDateTime::from(dt).try_into()
+    }
+}
+
+impl TryFrom<JulianDayUt> for OffsetDateTime {
+    type Error = SweError;
+
+    fn try_from(jd: JulianDayUt) -> Result<Self, Self::Error> {
+        UtcDateTime::from(jd).try_into()
+    }
+}
+
+impl TryFrom<JulianDayEt> for OffsetDateTime {
+    type Error = SweError;
+
+    fn try_from(jd: JulianDayEt) -> Result<Self, Self::Error> {
+        UtcDateTime::from(jd).try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_offset_is_applied() {
+        let date = Date::from_calendar_date(2002, Month::January, 1).unwrap();
+        let local = PrimitiveDateTime::new(date, Time::from_hms(17, 30, 0).unwrap())
+            .assume_offset(UtcOffset::from_hms(5, 30, 0).unwrap());
+        let utc = PrimitiveDateTime::new(date, Time::from_hms(12, 0, 0).unwrap())
+            .assume_offset(UtcOffset::UTC);
+
+        let a = JulianDayUt::try_from(local).unwrap();
+        let b = JulianDayUt::try_from(utc).unwrap();
+        assert!((a.0 - b.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn time_out_of_range() {
+        // year ~20000 is outside of the default time crate range
+        let err = OffsetDateTime::try_from(JulianDayUt(9.0e6)).unwrap_err();
+        assert!(matches!(err, SweError::DateOutOfRange { .. }));
+    }
+}
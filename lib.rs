@@ -20,3 +20,96 @@ pub mod safe;
 pub mod tuple_result;
 //pub use result::*;
 
+// Errors returned by the typed api below. Library errors from tuple_result convert into it.
+pub mod error;
+
+// Julian day types and conversions from/to UTC dates. With the chrono or time features
+// enabled, DateTime<Utc> and OffsetDateTime convert through the same leap second aware
+// functions of the library.
+pub mod julian_day;
+
+// Typed bodies, positions and observer locations on top of swe_calc/swe_calc_ut
+pub mod body;
+pub mod position;
+pub mod location;
+
+// Many bodies over many times into preallocated columns, reusing the C buffers
+pub mod batch;
+
+// Iterator over positions of a body at fixed steps, optionally with exact ingress and
+// station times
+pub mod ephemeris;
+
+// Chebyshev approximations of swe_calc for fast repeated lookups at nearby times
+pub mod cache;
+
+// Root and extremum finder over any quantity of time, the base of the event searches
+pub mod solver;
+
+// Shared stepping of the event iterators from one event to the next
+mod search;
+
+// Retrograde and direct stations and the shadow periods around them
+pub mod stations;
+
+// Crossings of a longitude and sign ingresses, using swe_solcross/swe_mooncross/
+// swe_helio_cross where the library has them
+pub mod crossing;
+
+// Aspect sets with orbs, applying/separating state and exact aspect times between bodies
+pub mod aspect;
+
+// House cusps, angles and house positions with swe_houses_ex/swe_house_pos
+pub mod houses;
+
+// Natal chart of positions, houses and aspects calculated together
+pub mod chart;
+
+// Transits to the points of a natal chart, with orb entry/exit and exact hits
+pub mod transits;
+
+// Secondary progressions and solar arc directions
+pub mod progressions;
+
+// Solar, lunar and planetary returns and return charts
+pub mod returns;
+
+// Synastry grids, midpoint composite and Davison charts
+pub mod relationship;
+
+// Phase angle, illumination, elongation and diameter with swe_pheno
+pub mod phenomena;
+
+// New, quarter and full moons (and the octants between them)
+pub mod lunar_phases;
+
+// Catalog of solar and lunar eclipses with magnitude, gamma and Saros numbers
+pub mod eclipses;
+
+// Central line and shadow limits of solar eclipses, with GeoJSON output
+pub mod eclipse_path;
+
+// Local eclipse magnitude and obscuration over a region, with contour lines
+pub mod eclipse_grid;
+
+// Occultations of planets and fixed stars by the Moon, globally and for an observer
+pub mod occultations;
+
+// Rise, set and meridian transits, and a daily almanac with the twilights
+pub mod almanac;
+
+// Rise and set over a local horizon profile, for observers in valleys or mountains
+pub mod horizon;
+
+// Heliacal risings and settings with typed observer and atmosphere conditions
+pub mod heliacal;
+
+// Browsing and searching the fixed star catalogue
+pub mod stars;
+
+// Fixed stars defined in Rust, written to a temporary star file the library reads
+pub mod custom_stars;
+
+// Setup shared by the tests of the modules above
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,19 @@
+// Geographic position of an observer. Longitude is positive east, latitude positive
+// north, both in degrees. Altitude is meters above sea level.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GeoLocation {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub altitude: f64,
+}
+
+impl GeoLocation {
+    pub fn new(longitude: f64, latitude: f64, altitude: f64) -> Self {
+        GeoLocation { longitude, latitude, altitude }
+    }
+
+    // The geopos array most functions of the library take
+    pub fn to_geopos(&self) -> [f64; 3] {
+        [self.longitude, self.latitude, self.altitude]
+    }
+}
@@ -0,0 +1,180 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::calc_ut;
+use crate::raw;
+use crate::search::{next_event, EventSearch};
+use crate::solver::{longitude_difference, EventKind, Solver};
+
+// A quarter (90 degrees) of elongation takes at most about 8.5 days
+const WINDOW: f64 = 9.;
+const STEP: f64 = 1.;
+
+// The eight phases by elongation of the Moon from the Sun, in steps of 45 degrees. The
+// quarters are moments, the octants between them are named after the phase they start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LunarPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl LunarPhase {
+    pub const ALL: [LunarPhase; 8] = [
+        LunarPhase::NewMoon,
+        LunarPhase::WaxingCrescent,
+        LunarPhase::FirstQuarter,
+        LunarPhase::WaxingGibbous,
+        LunarPhase::FullMoon,
+        LunarPhase::WaningGibbous,
+        LunarPhase::LastQuarter,
+        LunarPhase::WaningCrescent,
+    ];
+
+    // Elongation of the Moon east of the Sun where the phase begins
+    pub fn elongation(self) -> f64 {
+        self as usize as f64 * 45.
+    }
+
+    // Phase of an elongation (Moon minus Sun longitude) in degrees
+    pub fn from_elongation(elongation: f64) -> LunarPhase {
+        let i = (elongation.rem_euclid(360.) / 45.) as usize;
+        LunarPhase::ALL[i.min(7)]
+    }
+
+    pub fn is_quarter(self) -> bool {
+        matches!(
+            self,
+            LunarPhase::NewMoon
+                | LunarPhase::FirstQuarter
+                | LunarPhase::FullMoon
+                | LunarPhase::LastQuarter
+        )
+    }
+}
+
+fn elongation(tjd_ut: f64, iflag: i32) -> Result<f64, SweError> {
+    let moon = calc_ut(Body::Moon, tjd_ut, iflag)?;
+    let sun = calc_ut(Body::Sun, tjd_ut, iflag)?;
+    Ok(moon.longitude - sun.longitude)
+}
+
+// Phase the Moon is in at tjd_ut
+pub fn lunar_phase_at(tjd_ut: f64, iflag: i32) -> Result<LunarPhase, SweError> {
+    Ok(LunarPhase::from_elongation(elongation(tjd_ut, iflag)?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseEvent {
+    pub tjd_ut: f64,
+    pub phase: LunarPhase,
+}
+
+// New, first quarter, full and last quarter moons between start and end (UT), with the
+// octants in between if octants is true
+pub fn lunar_phases(start: f64, end: f64, octants: bool, iflag: i32) -> LunarPhases {
+    LunarPhases {
+        tjd_ut: start,
+        end,
+        octants,
+        iflag: iflag & !(raw::SEFLG_SPEED as i32),
+    }
+}
+
+pub struct LunarPhases {
+    tjd_ut: f64,
+    end: f64,
+    octants: bool,
+    iflag: i32,
+}
+
+impl EventSearch for LunarPhases {
+    type Event = PhaseEvent;
+    // about a second
+    const AFTER: f64 = 1e-5;
+
+    fn cursor(&mut self) -> &mut f64 {
+        &mut self.tjd_ut
+    }
+
+    fn end(&self) -> f64 {
+        self.end
+    }
+
+    fn search(&mut self) -> Result<Option<PhaseEvent>, SweError> {
+        let mut phase = lunar_phase_at(self.tjd_ut, self.iflag)?;
+        loop {
+            phase = LunarPhase::ALL[(phase as usize + 1) % 8];
+            if self.octants || phase.is_quarter() {
+                break;
+            }
+        }
+
+        let f = longitude_difference(Body::Moon, Body::Sun, self.iflag, phase.elongation());
+        let roots = Solver::new(STEP)
+            .angular(true)
+            .roots(f, self.tjd_ut, self.tjd_ut + WINDOW)?;
+        let tjd_ut = match roots.iter().find(|r| r.kind == EventKind::Ascending) {
+            Some(root) => root.tjd,
+            None => {
+                return Err(SweError::NotFound(format!(
+                    "{:?} within {} days of {}",
+                    phase, WINDOW, self.tjd_ut
+                )))
+            }
+        };
+
+        if tjd_ut > self.end {
+            return Ok(None);
+        }
+
+        self.tjd_ut = tjd_ut + Self::AFTER;
+        Ok(Some(PhaseEvent { tjd_ut, phase }))
+    }
+}
+
+impl Iterator for LunarPhases {
+    type Item = Result<PhaseEvent, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarters_in_january_2002() {
+        // new moon Jan 13 13:29, first quarter Jan 21 17:46, full moon Jan 28 22:50 UT
+        let phases: Vec<PhaseEvent> = lunar_phases(2452285.5, 2452305.5, false, 0)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(phases.len(), 3);
+        assert_eq!(phases[0].phase, LunarPhase::NewMoon);
+        assert!((phases[0].tjd_ut - 2452288.062).abs() < 0.01);
+        assert_eq!(phases[1].phase, LunarPhase::FirstQuarter);
+        assert!((phases[1].tjd_ut - 2452296.240).abs() < 0.01);
+        assert_eq!(phases[2].phase, LunarPhase::FullMoon);
+        assert!((phases[2].tjd_ut - 2452303.451).abs() < 0.01);
+    }
+
+    #[test]
+    fn octants_alternate_with_quarters() {
+        let phases: Vec<PhaseEvent> = lunar_phases(2452285.5, 2452345.5, true, 0)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert!(phases.len() >= 15);
+        for pair in phases.windows(2) {
+            assert_eq!(pair[1].phase as usize, (pair[0].phase as usize + 1) % 8);
+            assert!(pair[1].tjd_ut - pair[0].tjd_ut < 4.5);
+        }
+    }
+}
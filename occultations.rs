@@ -0,0 +1,284 @@
+use crate::body::Body;
+use crate::eclipses::EclipseType;
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::raw;
+use crate::search::{next_event, EventSearch};
+use crate::tuple_result;
+
+// What the Moon passes in front of. Stars are looked up by name or nomenclature in the
+// fixed star file, e.g. "Aldebaran" or ",alTau".
+#[derive(Debug, Clone, PartialEq)]
+pub enum Occulted {
+    Body(Body),
+    Star(String),
+}
+
+impl From<Body> for Occulted {
+    fn from(body: Body) -> Self {
+        Occulted::Body(body)
+    }
+}
+
+impl Occulted {
+    // ipl and starname for swe_lun_occult_*, the library ignores ipl if starname is set
+    fn ipl_starname(&self) -> (i32, &str) {
+        match self {
+            Occulted::Body(body) => (body.ipl(), ""),
+            Occulted::Star(name) => (0, name.as_str()),
+        }
+    }
+}
+
+// An occultation seen from somewhere on the Earth, from swe_lun_occult_when_glob
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occultation {
+    pub occulted: Occulted,
+    // Total if the object disappears completely somewhere, partial if the Moon only grazes
+    // it
+    pub occultation_type: EclipseType,
+    // The center of the Moon passes over the object as seen from some place
+    pub central: bool,
+    // Greatest occultation (UT)
+    pub tjd_max: f64,
+    // First and last contact anywhere on Earth (UT)
+    pub tjd_begin: f64,
+    pub tjd_end: f64,
+}
+
+// An occultation seen by one observer, from swe_lun_occult_when_loc. Disappearance and
+// reappearance are the first and last contact with the Moon's limb.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalOccultation {
+    pub occulted: Occulted,
+    pub occultation_type: EclipseType,
+    // Greatest occultation, disappearance and reappearance (UT)
+    pub tjd_max: f64,
+    pub disappearance: f64,
+    pub reappearance: f64,
+    // The object is above the horizon at these moments
+    pub max_visible: bool,
+    pub disappearance_visible: bool,
+    pub reappearance_visible: bool,
+    // The Sun is up at these moments
+    pub disappearance_in_daylight: bool,
+    pub reappearance_in_daylight: bool,
+    // True altitude of the object at greatest occultation in degrees
+    pub altitude: f64,
+}
+
+impl LocalOccultation {
+    // Some part of the occultation can be watched with the object above the horizon and
+    // the Sun below it
+    pub fn is_visible_at_night(&self) -> bool {
+        (self.disappearance_visible && !self.disappearance_in_daylight)
+            || (self.reappearance_visible && !self.reappearance_in_daylight)
+    }
+}
+
+// Where the center of the Moon's shadow on the Earth, the point where the object is
+// occulted centrally, is at tjd_ut. Fails for times without a central occultation.
+pub fn occultation_where(
+    occulted: &Occulted,
+    tjd_ut: f64,
+    iflag: i32,
+) -> Result<GeoLocation, SweError> {
+    let (ipl, starname) = occulted.ipl_starname();
+    let (flags, geopos, _) =
+        unsafe { tuple_result::swe_lun_occult_where(tjd_ut, ipl, starname, iflag)? };
+    if flags & raw::SE_ECL_CENTRAL as i32 == 0 {
+        return Err(SweError::NotFound(format!(
+            "central occultation at {}",
+            tjd_ut
+        )));
+    }
+
+    Ok(GeoLocation::new(geopos[0], geopos[1], 0.))
+}
+
+// Occultations of a planet or star by the Moon between start and end (UT), anywhere on
+// the Earth
+pub fn occultations(occulted: Occulted, start: f64, end: f64, iflag: i32) -> Occultations {
+    Occultations {
+        occulted,
+        iflag,
+        tjd_ut: start,
+        end,
+    }
+}
+
+pub struct Occultations {
+    occulted: Occulted,
+    iflag: i32,
+    tjd_ut: f64,
+    end: f64,
+}
+
+impl EventSearch for Occultations {
+    type Event = Occultation;
+    // Occultations of the same object are a sidereal month apart
+    const AFTER: f64 = 1.;
+
+    fn cursor(&mut self) -> &mut f64 {
+        &mut self.tjd_ut
+    }
+
+    fn end(&self) -> f64 {
+        self.end
+    }
+
+    fn search(&mut self) -> Result<Option<Occultation>, SweError> {
+        let (ipl, starname) = self.occulted.ipl_starname();
+        let (flags, tret) = unsafe {
+            tuple_result::swe_lun_occult_when_glob(self.tjd_ut, ipl, starname, self.iflag, 0, 0)?
+        };
+
+        // 0 if the object is too far from the ecliptic to be occulted at this time
+        if flags == 0 || tret[0] > self.end {
+            return Ok(None);
+        }
+        self.tjd_ut = tret[0] + Self::AFTER;
+
+        Ok(Some(Occultation {
+            occulted: self.occulted.clone(),
+            occultation_type: EclipseType::from_flags(flags).unwrap_or(EclipseType::Partial),
+            central: flags & raw::SE_ECL_CENTRAL as i32 != 0,
+            tjd_max: tret[0],
+            tjd_begin: tret[2],
+            tjd_end: tret[3],
+        }))
+    }
+}
+
+impl Iterator for Occultations {
+    type Item = Result<Occultation, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_event(self)
+    }
+}
+
+// Occultations of a planet or star by the Moon between start and end (UT) as seen from
+// location. Occultations that happen entirely below the horizon are skipped by the
+// library.
+pub fn local_occultations(
+    occulted: Occulted,
+    location: GeoLocation,
+    start: f64,
+    end: f64,
+    iflag: i32,
+) -> LocalOccultations {
+    LocalOccultations {
+        occulted,
+        location,
+        iflag,
+        tjd_ut: start,
+        end,
+    }
+}
+
+pub struct LocalOccultations {
+    occulted: Occulted,
+    location: GeoLocation,
+    iflag: i32,
+    tjd_ut: f64,
+    end: f64,
+}
+
+impl EventSearch for LocalOccultations {
+    type Event = LocalOccultation;
+    const AFTER: f64 = 1.;
+
+    fn cursor(&mut self) -> &mut f64 {
+        &mut self.tjd_ut
+    }
+
+    fn end(&self) -> f64 {
+        self.end
+    }
+
+    fn search(&mut self) -> Result<Option<LocalOccultation>, SweError> {
+        let (ipl, starname) = self.occulted.ipl_starname();
+        let (flags, tret, attr) = unsafe {
+            tuple_result::swe_lun_occult_when_loc(
+                self.tjd_ut,
+                ipl,
+                starname,
+                self.iflag,
+                self.location.to_geopos(),
+                0,
+            )?
+        };
+
+        if flags == 0 || tret[0] > self.end {
+            return Ok(None);
+        }
+        self.tjd_ut = tret[0] + Self::AFTER;
+
+        let has = |flag: u32| flags & flag as i32 != 0;
+        Ok(Some(LocalOccultation {
+            occulted: self.occulted.clone(),
+            occultation_type: EclipseType::from_flags(flags).unwrap_or(EclipseType::Partial),
+            tjd_max: tret[0],
+            disappearance: tret[1],
+            reappearance: tret[4],
+            max_visible: has(raw::SE_ECL_MAX_VISIBLE),
+            disappearance_visible: has(raw::SE_ECL_1ST_VISIBLE),
+            reappearance_visible: has(raw::SE_ECL_4TH_VISIBLE),
+            disappearance_in_daylight: has(raw::SE_ECL_OCC_BEG_DAYLIGHT),
+            reappearance_in_daylight: has(raw::SE_ECL_OCC_END_DAYLIGHT),
+            altitude: attr[5],
+        }))
+    }
+}
+
+impl Iterator for LocalOccultations {
+    type Item = Result<LocalOccultation, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mars was occulted on 2022-12-08 around 5h UT, seen from Europe and North America
+    const MARS_2022: f64 = 2459921.7;
+
+    #[test]
+    fn occultation_of_mars_2022() {
+        let found: Vec<Occultation> = occultations(Body::Mars.into(), 2459914.5, 2459925.5, 0)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        let o = &found[0];
+        assert!((o.tjd_max - MARS_2022).abs() < 0.15);
+        assert!(o.tjd_begin < o.tjd_max && o.tjd_max < o.tjd_end);
+
+        let center = occultation_where(&o.occulted, o.tjd_max, 0).unwrap();
+        assert!(center.latitude > 0.);
+
+        // longer than the star name buffers of the library
+        let long = Occulted::Star("a".repeat(300));
+        assert!(occultation_where(&long, o.tjd_max, 0).is_err());
+        let mut search = occultations(long, 2459914.5, 2459925.5, 0);
+        assert!(search.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn occultation_of_mars_seen_from_washington() {
+        let washington = GeoLocation::new(-77.04, 38.9, 0.);
+        let o = local_occultations(Body::Mars.into(), washington, 2459914.5, 2459925.5, 0)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert!((o.tjd_max - MARS_2022).abs() < 0.15);
+        assert!(o.disappearance < o.tjd_max && o.tjd_max < o.reappearance);
+        assert!(o.max_visible && o.altitude > 0.);
+        assert!(o.is_visible_at_night());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::tuple_result;
+
+// The phenomena swe_pheno fills in attr
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Phenomena {
+    // Angle Sun-body-Earth in degrees, 0 when fully lit
+    pub phase_angle: f64,
+    // Illuminated fraction of the disc, 0..1
+    pub illumination: f64,
+    // Angular distance from the Sun in degrees
+    pub elongation: f64,
+    // Apparent diameter of the disc in degrees
+    pub diameter: f64,
+    pub magnitude: f64,
+}
+
+impl From<[f64; 20]> for Phenomena {
+    fn from(attr: [f64; 20]) -> Self {
+        Phenomena {
+            phase_angle: attr[0],
+            illumination: attr[1],
+            elongation: attr[2],
+            diameter: attr[3],
+            magnitude: attr[4],
+        }
+    }
+}
+
+pub fn pheno(body: Body, tjd_et: f64, iflag: i32) -> Result<Phenomena, SweError> {
+    let (_, attr) = unsafe { tuple_result::swe_pheno(tjd_et, body.ipl(), iflag)? };
+    Ok(Phenomena::from(attr))
+}
+
+pub fn pheno_ut(body: Body, tjd_ut: f64, iflag: i32) -> Result<Phenomena, SweError> {
+    let (_, attr) = unsafe { tuple_result::swe_pheno_ut(tjd_ut, body.ipl(), iflag)? };
+    Ok(Phenomena::from(attr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_moon_is_fully_lit() {
+        // full moon 2002-01-28 22:50 UT
+        let p = pheno_ut(Body::Moon, 2452303.451, 0).unwrap();
+
+        assert!(p.illumination > 0.99);
+        assert!(p.phase_angle < 5.);
+        assert!(p.diameter > 0.48 && p.diameter < 0.57);
+    }
+}
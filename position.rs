@@ -0,0 +1,101 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::tuple_result;
+
+// The six values swe_calc fills in xx. With the default flags these are ecliptic
+// longitude/latitude in degrees and distance in AU. Speeds are per day and only set
+// when SEFLG_SPEED is passed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub distance: f64,
+    pub longitude_speed: f64,
+    pub latitude_speed: f64,
+    pub distance_speed: f64,
+}
+
+impl Position {
+    pub fn is_retrograde(&self) -> bool {
+        self.longitude_speed < 0.
+    }
+
+    pub fn sign(&self) -> Sign {
+        Sign::from_longitude(self.longitude)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Sign {
+    Aries,
+    Taurus,
+    Gemini,
+    Cancer,
+    Leo,
+    Virgo,
+    Libra,
+    Scorpio,
+    Sagittarius,
+    Capricorn,
+    Aquarius,
+    Pisces,
+}
+
+impl Sign {
+    pub const ALL: [Sign; 12] = [
+        Sign::Aries,
+        Sign::Taurus,
+        Sign::Gemini,
+        Sign::Cancer,
+        Sign::Leo,
+        Sign::Virgo,
+        Sign::Libra,
+        Sign::Scorpio,
+        Sign::Sagittarius,
+        Sign::Capricorn,
+        Sign::Aquarius,
+        Sign::Pisces,
+    ];
+
+    // Longitude in degrees, any value is normalized to 0..360 first
+    pub fn from_longitude(longitude: f64) -> Sign {
+        let i = (longitude.rem_euclid(360.) / 30.) as usize;
+        Sign::ALL[i.min(11)]
+    }
+
+    // Longitude where the sign begins
+    pub fn start_longitude(self) -> f64 {
+        self as usize as f64 * 30.
+    }
+
+    pub fn next(self) -> Sign {
+        Sign::ALL[(self as usize + 1) % 12]
+    }
+
+    pub fn previous(self) -> Sign {
+        Sign::ALL[(self as usize + 11) % 12]
+    }
+}
+
+impl From<[f64; 6]> for Position {
+    fn from(xx: [f64; 6]) -> Self {
+        Position {
+            longitude: xx[0],
+            latitude: xx[1],
+            distance: xx[2],
+            longitude_speed: xx[3],
+            latitude_speed: xx[4],
+            distance_speed: xx[5],
+        }
+    }
+}
+
+pub fn calc(body: Body, tjd_et: f64, iflag: i32) -> Result<Position, SweError> {
+    let (_, xx) = unsafe { tuple_result::swe_calc(tjd_et, body.ipl(), iflag)? };
+    Ok(Position::from(xx))
+}
+
+pub fn calc_ut(body: Body, tjd_ut: f64, iflag: i32) -> Result<Position, SweError> {
+    let (_, xx) = unsafe { tuple_result::swe_calc_ut(tjd_ut, body.ipl(), iflag)? };
+    Ok(Position::from(xx))
+}
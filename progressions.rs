@@ -0,0 +1,212 @@
+use crate::aspect::AspectSet;
+use crate::body::Body;
+use crate::chart::Chart;
+use crate::error::SweError;
+use crate::houses::{houses_armc, mc_to_armc};
+use crate::position::{calc_ut, Position};
+use crate::raw;
+use crate::tuple_result;
+
+// Days in a tropical year, the "year" of day-for-a-year
+pub const TROPICAL_YEAR: f64 = 365.24219;
+// Mean daily motion of the Sun in degrees
+pub const NAIBOD_RATE: f64 = 0.98564733;
+
+// How the MC of a secondary progression moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McProgression {
+    // Natal MC plus the arc the progressed Sun has moved in longitude
+    SolarArc,
+    // Natal ARMC plus the Naibod rate per year, in right ascension
+    Naibod,
+    // Natal MC plus the Naibod rate per year, in longitude
+    MeanSun,
+}
+
+// The moment whose positions are progressed to target, one day after birth for every
+// tropical year of life
+pub fn progressed_tjd(natal_tjd_ut: f64, target_tjd_ut: f64) -> f64 {
+    natal_tjd_ut + (target_tjd_ut - natal_tjd_ut) / TROPICAL_YEAR
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progressed {
+    pub target_tjd_ut: f64,
+    // Progressed Sun minus natal Sun, 0..360
+    pub solar_arc: f64,
+    // Chart for the progressed moment. chart.tjd_ut is the progressed time, not target.
+    pub chart: Chart,
+}
+
+fn solar_arc_at(natal: &Chart, progressed_tjd_ut: f64) -> Result<f64, SweError> {
+    let natal_sun = calc_ut(Body::Sun, natal.tjd_ut, natal.iflag)?;
+    let progressed_sun = calc_ut(Body::Sun, progressed_tjd_ut, natal.iflag)?;
+    Ok((progressed_sun.longitude - natal_sun.longitude).rem_euclid(360.))
+}
+
+fn obliquity(tjd_ut: f64) -> Result<f64, SweError> {
+    let (_, nut) = unsafe { tuple_result::swe_calc_ut(tjd_ut, raw::SE_ECL_NUT, 0)? };
+    Ok(nut[0])
+}
+
+// With the nutation of the natal flags, like the progressed positions
+fn ayanamsa(natal: &Chart, tjd_ut: f64) -> Result<f64, SweError> {
+    match natal.iflag & raw::SEFLG_SIDEREAL as i32 {
+        0 => Ok(0.),
+        _ => Ok(unsafe { tuple_result::swe_get_ayanamsa_ex_ut(tjd_ut, natal.iflag)?.1 }),
+    }
+}
+
+// Secondary progressed chart of natal for target (UT). The bodies of the natal chart are
+// calculated for the progressed moment with the natal flags, sidereal mode and
+// topocentric location, the houses for the progressed MC at the natal location. The
+// library's previous mode and location are set again afterwards.
+pub fn secondary_progression(
+    natal: &Chart,
+    target_tjd_ut: f64,
+    mc: McProgression,
+    aspects: &AspectSet,
+) -> Result<Progressed, SweError> {
+    natal.with_library_state(|| progress(natal, target_tjd_ut, mc, aspects))
+}
+
+fn progress(
+    natal: &Chart,
+    target_tjd_ut: f64,
+    mc: McProgression,
+    aspects: &AspectSet,
+) -> Result<Progressed, SweError> {
+    let tjd_ut = progressed_tjd(natal.tjd_ut, target_tjd_ut);
+    let solar_arc = solar_arc_at(natal, tjd_ut)?;
+    let years = (target_tjd_ut - natal.tjd_ut) / TROPICAL_YEAR;
+
+    let eps = obliquity(tjd_ut)?;
+    let ayanamsa = ayanamsa(natal, tjd_ut)?;
+    // the library works with tropical longitudes
+    let natal_mc = natal.houses.angles.mc + natal.houses.ayanamsa;
+    let armc = match mc {
+        McProgression::SolarArc => mc_to_armc(natal_mc + solar_arc, eps),
+        McProgression::Naibod => natal.houses.angles.armc + years * NAIBOD_RATE,
+        McProgression::MeanSun => mc_to_armc(natal_mc + years * NAIBOD_RATE, eps),
+    };
+    let houses = houses_armc(
+        armc.rem_euclid(360.),
+        natal.location.latitude,
+        eps,
+        natal.houses.system,
+        ayanamsa,
+    )?;
+
+    let positions = natal
+        .bodies
+        .iter()
+        .map(|b| Ok((b.body, calc_ut(b.body, tjd_ut, natal.iflag)?)))
+        .collect::<Result<Vec<_>, SweError>>()?;
+
+    let chart = Chart::from_parts(
+        tjd_ut,
+        natal.location,
+        natal.zodiac,
+        natal.iflag,
+        positions,
+        houses,
+        aspects,
+    )?;
+
+    Ok(Progressed {
+        target_tjd_ut,
+        solar_arc,
+        chart,
+    })
+}
+
+// Solar arc directed chart of natal for target (UT): every natal body and the MC moved
+// forward by the solar arc of the secondary progression, the other houses from the
+// directed MC with the natal obliquity. chart.tjd_ut is target. The solar arc is
+// calculated with the natal sidereal mode like in secondary_progression.
+pub fn solar_arc_directions(
+    natal: &Chart,
+    target_tjd_ut: f64,
+    aspects: &AspectSet,
+) -> Result<Progressed, SweError> {
+    natal.with_library_state(|| direct(natal, target_tjd_ut, aspects))
+}
+
+fn direct(natal: &Chart, target_tjd_ut: f64, aspects: &AspectSet) -> Result<Progressed, SweError> {
+    let solar_arc = solar_arc_at(natal, progressed_tjd(natal.tjd_ut, target_tjd_ut))?;
+
+    let natal_mc = natal.houses.angles.mc + natal.houses.ayanamsa;
+    let armc = mc_to_armc(natal_mc + solar_arc, natal.houses.obliquity);
+    let houses = houses_armc(
+        armc,
+        natal.location.latitude,
+        natal.houses.obliquity,
+        natal.houses.system,
+        natal.houses.ayanamsa,
+    )?;
+
+    let positions = natal
+        .bodies
+        .iter()
+        .map(|b| {
+            let position = Position {
+                longitude: (b.position.longitude + solar_arc).rem_euclid(360.),
+                ..b.position
+            };
+            (b.body, position)
+        })
+        .collect();
+
+    let chart = Chart::from_parts(
+        target_tjd_ut,
+        natal.location,
+        natal.zodiac,
+        natal.iflag,
+        positions,
+        houses,
+        aspects,
+    )?;
+
+    Ok(Progressed {
+        target_tjd_ut,
+        solar_arc,
+        chart,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::ChartOptions;
+    use crate::location::GeoLocation;
+
+    #[test]
+    fn progressed_sun_moves_about_a_degree_a_year() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let natal = Chart::new(2444239.5, zurich, &ChartOptions::default()).unwrap();
+        let target = natal.tjd_ut + 30. * TROPICAL_YEAR;
+
+        let p = secondary_progression(&natal, target, McProgression::SolarArc, &AspectSet::major())
+            .unwrap();
+        assert!((p.chart.tjd_ut - natal.tjd_ut - 30.).abs() < 1e-9);
+        assert!((p.solar_arc - 30.).abs() < 1.5);
+
+        // with a solar arc MC the MC moves exactly as far as the Sun
+        let mc_arc = (p.chart.houses.angles.mc - natal.houses.angles.mc).rem_euclid(360.);
+        assert!((mc_arc - p.solar_arc).abs() < 1e-6);
+    }
+
+    #[test]
+    fn directed_bodies_keep_their_distances() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let natal = Chart::new(2444239.5, zurich, &ChartOptions::default()).unwrap();
+        let target = natal.tjd_ut + 30. * TROPICAL_YEAR;
+
+        let d = solar_arc_directions(&natal, target, &AspectSet::major()).unwrap();
+        for (n, b) in natal.bodies.iter().zip(&d.chart.bodies) {
+            let arc = (b.position.longitude - n.position.longitude).rem_euclid(360.);
+            assert!((arc - d.solar_arc).abs() < 1e-9);
+        }
+        assert_eq!(natal.aspects.len(), d.chart.aspects.len());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::aspect::{aspect_between, AspectSet};
+use crate::chart::{aspect_grid, Chart, ChartAspect, ChartBody, ChartOptions};
+use crate::error::SweError;
+use crate::houses::{Angles, Houses};
+use crate::location::GeoLocation;
+use crate::position::Position;
+use crate::tuple_result;
+
+// Midpoint on the shorter arc, with swe_deg_midp
+fn midpoint(x: f64, y: f64) -> f64 {
+    unsafe { tuple_result::swe_deg_midp(x, y) }
+}
+
+// Aspects from every body of a (first) to every body of b (second)
+pub fn synastry(a: &Chart, b: &Chart, set: &AspectSet) -> Vec<ChartAspect> {
+    let mut aspects = Vec::new();
+    for x in &a.bodies {
+        for y in &b.bodies {
+            if let Some(aspect) = aspect_between(&x.position, &y.position, set) {
+                aspects.push(ChartAspect {
+                    first: x.body,
+                    second: y.body,
+                    aspect,
+                });
+            }
+        }
+    }
+    aspects
+}
+
+fn midpoint_position(a: &Position, b: &Position) -> Position {
+    Position {
+        longitude: midpoint(a.longitude, b.longitude),
+        latitude: (a.latitude + b.latitude) / 2.,
+        distance: (a.distance + b.distance) / 2.,
+        longitude_speed: (a.longitude_speed + b.longitude_speed) / 2.,
+        latitude_speed: (a.latitude_speed + b.latitude_speed) / 2.,
+        distance_speed: (a.distance_speed + b.distance_speed) / 2.,
+    }
+}
+
+// Midpoint of two places: longitude on the shorter arc, so across the date line if that
+// is closer, and the mean latitude and altitude
+pub fn midpoint_location(a: &GeoLocation, b: &GeoLocation) -> GeoLocation {
+    let longitude = midpoint(a.longitude, b.longitude);
+    GeoLocation {
+        longitude: if longitude > 180. {
+            longitude - 360.
+        } else {
+            longitude
+        },
+        latitude: (a.latitude + b.latitude) / 2.,
+        altitude: (a.altitude + b.altitude) / 2.,
+    }
+}
+
+// Midpoint composite chart. Bodies in both charts get the midpoint of their positions,
+// cusps and angles the midpoints of the cusps and angles of a and b. The time and
+// location are the midpoints of the two charts; the zodiac, flags and house system are
+// those of a. Bodies are placed in houses by the composite cusps, as there is no sidereal
+// time the composite houses belong to.
+pub fn composite(a: &Chart, b: &Chart, set: &AspectSet) -> Chart {
+    let n = a.houses.cusps.len().min(b.houses.cusps.len());
+    let cusps: Vec<f64> = (0..n)
+        .map(|i| midpoint(a.houses.cusps[i], b.houses.cusps[i]))
+        .collect();
+
+    let (x, y) = (&a.houses.angles, &b.houses.angles);
+    let angles = Angles {
+        ascendant: midpoint(x.ascendant, y.ascendant),
+        mc: midpoint(x.mc, y.mc),
+        armc: midpoint(x.armc, y.armc),
+        vertex: midpoint(x.vertex, y.vertex),
+        equatorial_ascendant: midpoint(x.equatorial_ascendant, y.equatorial_ascendant),
+        co_ascendant_koch: midpoint(x.co_ascendant_koch, y.co_ascendant_koch),
+        co_ascendant_munkasey: midpoint(x.co_ascendant_munkasey, y.co_ascendant_munkasey),
+        polar_ascendant: midpoint(x.polar_ascendant, y.polar_ascendant),
+    };
+
+    let location = midpoint_location(&a.location, &b.location);
+    let houses = Houses {
+        system: a.houses.system,
+        cusps,
+        angles,
+        obliquity: (a.houses.obliquity + b.houses.obliquity) / 2.,
+        latitude: location.latitude,
+        ayanamsa: (a.houses.ayanamsa + b.houses.ayanamsa) / 2.,
+    };
+
+    let bodies: Vec<ChartBody> = a
+        .bodies
+        .iter()
+        .filter_map(|x| {
+            let y = b.bodies.iter().find(|y| y.body == x.body)?;
+            let position = midpoint_position(&x.position, &y.position);
+            Some(ChartBody {
+                body: x.body,
+                position,
+                house_position: houses.cusp_position(position.longitude),
+            })
+        })
+        .collect();
+
+    let aspects = aspect_grid(&bodies, set);
+
+    Chart {
+        tjd_ut: (a.tjd_ut + b.tjd_ut) / 2.,
+        location,
+        zodiac: a.zodiac,
+        iflag: a.iflag,
+        bodies,
+        houses,
+        aspects,
+    }
+}
+
+// Davison relationship chart: an ordinary chart calculated for the midpoint in time and
+// the midpoint in space of a and b
+pub fn davison(a: &Chart, b: &Chart, options: &ChartOptions) -> Result<Chart, SweError> {
+    let tjd_ut = (a.tjd_ut + b.tjd_ut) / 2.;
+    Chart::new(tjd_ut, midpoint_location(&a.location, &b.location), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aspect::AspectKind;
+    use crate::solver::normalize_angle;
+
+    fn charts() -> (Chart, Chart) {
+        let options = ChartOptions::default();
+        let a = Chart::new(2444239.5, GeoLocation::new(170., 10., 0.), &options).unwrap();
+        let b = Chart::new(2445000.3, GeoLocation::new(-170., 30., 100.), &options).unwrap();
+        (a, b)
+    }
+
+    #[test]
+    fn composite_uses_the_shorter_arc() {
+        let (a, b) = charts();
+        let c = composite(&a, &b, &AspectSet::major());
+
+        assert_eq!(c.bodies.len(), a.bodies.len());
+        for ((x, y), z) in a.bodies.iter().zip(&b.bodies).zip(&c.bodies) {
+            let (x, y, z) = (
+                x.position.longitude,
+                y.position.longitude,
+                z.position.longitude,
+            );
+            let half = normalize_angle(x - y).abs() / 2.;
+            assert!((normalize_angle(z - x).abs() - half).abs() < 1e-9);
+            assert!((normalize_angle(z - y).abs() - half).abs() < 1e-9);
+        }
+        assert!(c.bodies.iter().all(|b| (1..=12).contains(&b.house())));
+        // across the date line
+        assert!((c.location.longitude.abs() - 180.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn davison_and_synastry() {
+        let (a, b) = charts();
+        let d = davison(&a, &b, &ChartOptions::default()).unwrap();
+        assert!((d.tjd_ut - (a.tjd_ut + b.tjd_ut) / 2.).abs() < 1e-9);
+        assert!((d.location.latitude - 20.).abs() < 1e-9);
+
+        // every body is conjunct itself
+        let set = AspectSet::new().with(AspectKind::Conjunction, 1.);
+        let grid = synastry(&a, &a, &set);
+        assert!(a
+            .bodies
+            .iter()
+            .all(|x| grid.iter().any(|g| g.first == x.body && g.second == x.body)));
+    }
+}
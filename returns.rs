@@ -0,0 +1,178 @@
+use crate::body::Body;
+use crate::chart::{Chart, ChartOptions, Zodiac};
+use crate::crossing::crossing;
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::position::calc_ut;
+use crate::progressions::TROPICAL_YEAR;
+use crate::raw;
+use crate::tuple_result;
+
+// General precession in longitude, 50.288" per year
+pub const PRECESSION_RATE: f64 = 50.288 / 3600.;
+
+// Skips over the return just found when searching for the next one, about a second
+const AFTER: f64 = 1e-5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnZodiac {
+    Tropical,
+    // With one of the SE_SIDM_* modes. Sets the library's sidereal mode.
+    Sidereal(i32),
+    // Tropical, with the natal longitude moved forward by the precession since birth.
+    // Gives the same moments as a sidereal return.
+    PrecessionCorrected,
+}
+
+impl ReturnZodiac {
+    fn flags(self, iflag: i32) -> i32 {
+        let iflag = iflag & !(raw::SEFLG_SPEED as i32);
+        match self {
+            ReturnZodiac::Sidereal(sid_mode) => {
+                unsafe { tuple_result::swe_set_sid_mode(sid_mode, 0., 0.) };
+                iflag | raw::SEFLG_SIDEREAL as i32
+            }
+            _ => iflag & !(raw::SEFLG_SIDEREAL as i32),
+        }
+    }
+}
+
+// First time (UT) after tjd_ut that body returns to its longitude at natal_tjd_ut. The
+// Sun and Moon use swe_solcross_ut/swe_mooncross_ut, other bodies the solver. A planet
+// near a station can return up to three times in a row, search again from the result
+// to find the others. A sidereal zodiac sets the library's sidereal mode, which stays set
+// after the call.
+pub fn return_time(
+    body: Body,
+    natal_tjd_ut: f64,
+    tjd_ut: f64,
+    zodiac: ReturnZodiac,
+    iflag: i32,
+) -> Result<f64, SweError> {
+    let iflag = zodiac.flags(iflag);
+    let natal_longitude = calc_ut(body, natal_tjd_ut, iflag)?.longitude;
+
+    match zodiac {
+        ReturnZodiac::PrecessionCorrected => {
+            // the target moves a few seconds of arc while the return is refined
+            let target = |t: f64| {
+                let years = (t - natal_tjd_ut) / TROPICAL_YEAR;
+                (natal_longitude + years * PRECESSION_RATE).rem_euclid(360.)
+            };
+            let mut t = crossing(body, target(tjd_ut), tjd_ut, iflag)?;
+            for _ in 0..5 {
+                let next = crossing(body, target(t), tjd_ut, iflag)?;
+                let done = (next - t).abs() < 1e-7;
+                t = next;
+                if done {
+                    break;
+                }
+            }
+            Ok(t)
+        }
+        _ => crossing(body, natal_longitude, tjd_ut, iflag),
+    }
+}
+
+// All returns of body between start and end (UT). Sets the sidereal mode like
+// return_time.
+pub fn returns(
+    body: Body,
+    natal_tjd_ut: f64,
+    start: f64,
+    end: f64,
+    zodiac: ReturnZodiac,
+    iflag: i32,
+) -> Result<Vec<f64>, SweError> {
+    let mut times = Vec::new();
+    let mut t = start;
+    loop {
+        t = match return_time(body, natal_tjd_ut, t, zodiac, iflag) {
+            Ok(t) if t <= end => t,
+            Ok(_) | Err(SweError::NotFound(_)) => return Ok(times),
+            Err(err) => return Err(err),
+        };
+        times.push(t);
+        t += AFTER;
+    }
+}
+
+// Chart for the first return of body after tjd_ut at location. A sidereal return makes a
+// sidereal chart with the same mode, otherwise the zodiac of options is used. Sets the
+// sidereal mode like return_time.
+pub fn return_chart(
+    body: Body,
+    natal_tjd_ut: f64,
+    tjd_ut: f64,
+    zodiac: ReturnZodiac,
+    location: GeoLocation,
+    options: &ChartOptions,
+) -> Result<Chart, SweError> {
+    let t = return_time(body, natal_tjd_ut, tjd_ut, zodiac, options.iflag)?;
+
+    match zodiac {
+        ReturnZodiac::Sidereal(sid_mode) => {
+            let options = ChartOptions {
+                zodiac: Zodiac::Sidereal(sid_mode),
+                ..options.clone()
+            };
+            Chart::new(t, location, &options)
+        }
+        _ => Chart::new(t, location, options),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NATAL: f64 = 2444239.5;
+
+    #[test]
+    fn solar_and_lunar_returns() {
+        let after = NATAL + 20. * 365.25;
+        let sun = return_time(Body::Sun, NATAL, after, ReturnZodiac::Tropical, 0).unwrap();
+        assert!(sun > after && sun < after + 366.);
+        let natal = calc_ut(Body::Sun, NATAL, 0).unwrap().longitude;
+        let now = calc_ut(Body::Sun, sun, 0).unwrap().longitude;
+        assert!((natal - now).abs() < 1e-5);
+
+        let moons = returns(
+            Body::Moon,
+            NATAL,
+            after,
+            after + 365.25,
+            ReturnZodiac::Tropical,
+            0,
+        )
+        .unwrap();
+        assert!(moons.len() == 13 || moons.len() == 14);
+        assert!(moons.windows(2).all(|w| (w[1] - w[0] - 27.32).abs() < 0.5));
+    }
+
+    #[test]
+    fn precession_corrected_return_is_later() {
+        let after = NATAL + 40. * 365.25 - 30.;
+        let tropical = return_time(Body::Sun, NATAL, after, ReturnZodiac::Tropical, 0).unwrap();
+        let corrected = return_time(
+            Body::Sun,
+            NATAL,
+            after,
+            ReturnZodiac::PrecessionCorrected,
+            0,
+        )
+        .unwrap();
+        let sidereal = return_time(
+            Body::Sun,
+            NATAL,
+            after,
+            ReturnZodiac::Sidereal(raw::SE_SIDM_FAGAN_BRADLEY as i32),
+            0,
+        )
+        .unwrap();
+
+        // 40 years of precession, about 0.56 degrees or 13 hours of solar motion
+        assert!((corrected - tropical - 0.55).abs() < 0.05);
+        assert!((corrected - sidereal).abs() < 0.01);
+    }
+}
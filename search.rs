@@ -0,0 +1,97 @@
+use crate::error::SweError;
+
+// An event search stepping forward from one event to the next until an end time. The
+// iterators of events implement it and call next_event from Iterator::next.
+pub(crate) trait EventSearch {
+    type Event;
+
+    // Skips over the event just found when searching for the next one
+    const AFTER: f64;
+
+    // Start of the next search (UT), moved past every event found
+    fn cursor(&mut self) -> &mut f64;
+
+    fn end(&self) -> f64;
+
+    // The next event from the cursor, None if there is none before the end
+    fn search(&mut self) -> Result<Option<Self::Event>, SweError>;
+}
+
+pub(crate) fn next_event<S: EventSearch>(search: &mut S) -> Option<Result<S::Event, SweError>> {
+    if *search.cursor() > search.end() {
+        return None;
+    }
+
+    match search.search() {
+        Ok(Some(event)) => Some(Ok(event)),
+        Ok(None) => {
+            *search.cursor() = f64::INFINITY;
+            None
+        }
+        Err(err) => {
+            // don't repeat the failing search forever
+            *search.cursor() = f64::INFINITY;
+            Some(Err(err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every whole day, failing at fail
+    struct Days {
+        tjd_ut: f64,
+        end: f64,
+        fail: f64,
+    }
+
+    impl EventSearch for Days {
+        type Event = f64;
+        const AFTER: f64 = 0.5;
+
+        fn cursor(&mut self) -> &mut f64 {
+            &mut self.tjd_ut
+        }
+
+        fn end(&self) -> f64 {
+            self.end
+        }
+
+        fn search(&mut self) -> Result<Option<f64>, SweError> {
+            let day = self.tjd_ut.ceil();
+            if day == self.fail {
+                return Err(SweError::NotFound(format!("day {}", day)));
+            }
+            if day > self.end {
+                return Ok(None);
+            }
+            self.tjd_ut = day + Self::AFTER;
+            Ok(Some(day))
+        }
+    }
+
+    #[test]
+    fn stops_at_the_end_and_after_errors() {
+        let mut days = Days {
+            tjd_ut: 0.5,
+            end: 3.5,
+            fail: 10.,
+        };
+        let found: Vec<f64> = std::iter::from_fn(|| next_event(&mut days))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found, [1., 2., 3.]);
+        assert!(next_event(&mut days).is_none());
+
+        let mut days = Days {
+            tjd_ut: 0.5,
+            end: 3.5,
+            fail: 2.,
+        };
+        assert_eq!(next_event(&mut days).unwrap().unwrap(), 1.);
+        assert!(next_event(&mut days).unwrap().is_err());
+        assert!(next_event(&mut days).is_none());
+    }
+}
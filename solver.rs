@@ -0,0 +1,462 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::location::GeoLocation;
+use crate::position::calc_ut;
+use crate::raw;
+use crate::tuple_result;
+
+const MAX_ITERATIONS: usize = 100;
+
+// Finds the times where a quantity crosses zero or has an extremum. The quantity is any
+// function of the Julian day, e.g. longitude_difference(Body::Moon, Body::Sun, 0, 0.)
+// crosses zero at every new moon.
+//
+// The range is sampled at step, every bracketed sign change is refined with Brent's
+// root finder and every bracketed extremum with Brent's minimizer. Events closer together
+// than step can be missed.
+//
+// For angular quantities (angular(true)) values are normalized to -180..180 and the
+// jump from 180 to -180 is not reported as a crossing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solver {
+    step: f64,
+    precision: f64,
+    angular: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    // Zero crossing from negative to positive
+    Ascending,
+    // Zero crossing from positive to negative
+    Descending,
+    Maximum,
+    Minimum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    pub tjd: f64,
+    pub value: f64,
+    pub kind: EventKind,
+}
+
+impl Solver {
+    // Step in days, the precision defaults to about 0.1 second
+    pub fn new(step: f64) -> Self {
+        assert!(step > 0., "solver step must be greater than zero");
+        Solver {
+            step,
+            precision: 1e-6,
+            angular: false,
+        }
+    }
+
+    pub fn precision(mut self, days: f64) -> Self {
+        self.precision = days;
+        self
+    }
+
+    pub fn angular(mut self, angular: bool) -> Self {
+        self.angular = angular;
+        self
+    }
+
+    fn value(&self, v: f64) -> f64 {
+        match self.angular {
+            true => normalize_angle(v),
+            false => v,
+        }
+    }
+
+    fn sample<F>(&self, f: &mut F, start: f64, end: f64) -> Result<Vec<(f64, f64)>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let n = ((end - start) / self.step).ceil().max(1.) as usize;
+        (0..=n)
+            .map(|i| {
+                let t = (start + i as f64 * self.step).min(end);
+                f(t).map(|v| (t, self.value(v)))
+            })
+            .collect()
+    }
+
+    pub fn roots<F>(&self, mut f: F, start: f64, end: f64) -> Result<Vec<Event>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let samples = self.sample(&mut f, start, end)?;
+        self.roots_in(&mut f, &samples)
+    }
+
+    pub fn extrema<F>(&self, mut f: F, start: f64, end: f64) -> Result<Vec<Event>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let samples = self.sample(&mut f, start, end)?;
+        self.extrema_in(&mut f, &samples)
+    }
+
+    // Roots and extrema ordered by time
+    pub fn events<F>(&self, mut f: F, start: f64, end: f64) -> Result<Vec<Event>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let samples = self.sample(&mut f, start, end)?;
+        let mut events = self.roots_in(&mut f, &samples)?;
+        events.extend(self.extrema_in(&mut f, &samples)?);
+        events.sort_by(|a, b| a.tjd.total_cmp(&b.tjd));
+
+        Ok(events)
+    }
+
+    fn roots_in<F>(&self, f: &mut F, samples: &[(f64, f64)]) -> Result<Vec<Event>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let mut events = Vec::new();
+        let mut g = |t: f64| f(t).map(|v| self.value(v));
+
+        for pair in samples.windows(2) {
+            let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+            if (v0 < 0.) == (v1 < 0.) {
+                continue;
+            }
+            // wrapping from 180 to -180 is not a crossing
+            if self.angular && (v1 - v0).abs() > 180. {
+                continue;
+            }
+
+            let (tjd, value) = brent_root(&mut g, t0, t1, v0, v1, self.precision)?;
+            let kind = match v0 < 0. {
+                true => EventKind::Ascending,
+                false => EventKind::Descending,
+            };
+            events.push(Event { tjd, value, kind });
+        }
+
+        Ok(events)
+    }
+
+    fn extrema_in<F>(&self, f: &mut F, samples: &[(f64, f64)]) -> Result<Vec<Event>, SweError>
+    where
+        F: FnMut(f64) -> Result<f64, SweError>,
+    {
+        let mut events = Vec::new();
+
+        for triple in samples.windows(3) {
+            let ((t0, v0), (t1, v1), (t2, v2)) = (triple[0], triple[1], triple[2]);
+            let d0 = self.value(v1 - v0);
+            let d1 = self.value(v2 - v1);
+            if d0 * d1 >= 0. {
+                continue;
+            }
+
+            let kind = match d0 > 0. {
+                true => EventKind::Maximum,
+                false => EventKind::Minimum,
+            };
+            let sign = match kind {
+                EventKind::Maximum => -1.,
+                _ => 1.,
+            };
+
+            // relative to v1 so angular values stay continuous around the extremum
+            let mut g = |t: f64| f(t).map(|v| sign * self.value(v - v1));
+            let (tjd, gv) = brent_min(&mut g, t0, t1, t2, 0., self.precision)?;
+            let value = self.value(v1 + sign * gv);
+            events.push(Event { tjd, value, kind });
+        }
+
+        Ok(events)
+    }
+}
+
+// Normalizes degrees to -180..180
+pub fn normalize_angle(x: f64) -> f64 {
+    (x + 180.).rem_euclid(360.) - 180.
+}
+
+// Longitude of a minus longitude of b minus angle, zero when b is angle degrees behind a.
+// Use with Solver::angular(true).
+pub fn longitude_difference(
+    a: Body,
+    b: Body,
+    iflag: i32,
+    angle: f64,
+) -> impl FnMut(f64) -> Result<f64, SweError> {
+    move |tjd_ut| {
+        let pa = calc_ut(a, tjd_ut, iflag)?;
+        let pb = calc_ut(b, tjd_ut, iflag)?;
+        Ok(normalize_angle(pa.longitude - pb.longitude - angle))
+    }
+}
+
+// Longitude minus a fixed longitude, zero when the body crosses it
+pub fn longitude_minus(
+    body: Body,
+    iflag: i32,
+    longitude: f64,
+) -> impl FnMut(f64) -> Result<f64, SweError> {
+    move |tjd_ut| {
+        let p = calc_ut(body, tjd_ut, iflag)?;
+        Ok(normalize_angle(p.longitude - longitude))
+    }
+}
+
+// Longitude speed, zero at the stations
+pub fn longitude_speed(body: Body, iflag: i32) -> impl FnMut(f64) -> Result<f64, SweError> {
+    let iflag = iflag | raw::SEFLG_SPEED as i32;
+    move |tjd_ut| Ok(calc_ut(body, tjd_ut, iflag)?.longitude_speed)
+}
+
+// The swe_azalt conversion for positions calculated with iflag
+pub(crate) fn azalt_flag(iflag: i32) -> i32 {
+    if iflag & raw::SEFLG_EQUATORIAL as i32 != 0 {
+        raw::SE_EQU2HOR as i32
+    } else {
+        raw::SE_ECL2HOR as i32
+    }
+}
+
+// True (unrefracted) altitude above the horizon from swe_azalt, minus horizon degrees
+pub fn altitude(
+    body: Body,
+    location: GeoLocation,
+    iflag: i32,
+    horizon: f64,
+) -> impl FnMut(f64) -> Result<f64, SweError> {
+    move |tjd_ut| {
+        let p = calc_ut(body, tjd_ut, iflag)?;
+        let xaz = unsafe {
+            tuple_result::swe_azalt(
+                tjd_ut,
+                azalt_flag(iflag),
+                location.to_geopos(),
+                0.,
+                0.,
+                [p.longitude, p.latitude, p.distance],
+            )
+        };
+
+        Ok(xaz[1] - horizon)
+    }
+}
+
+// Brent's root finder for a bracket a, b where fa and fb have opposite signs
+fn brent_root<F>(
+    f: &mut F,
+    mut a: f64,
+    mut b: f64,
+    mut fa: f64,
+    mut fb: f64,
+    tol: f64,
+) -> Result<(f64, f64), SweError>
+where
+    F: FnMut(f64) -> Result<f64, SweError>,
+{
+    let (mut c, mut fc) = (b, fb);
+    let mut d = b - a;
+    let mut e = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        if (fb > 0.) == (fc > 0.) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol1 = 2. * f64::EPSILON * b.abs() + 0.5 * tol;
+        let xm = 0.5 * (c - b);
+        if xm.abs() <= tol1 || fb == 0. {
+            return Ok((b, fb));
+        }
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // inverse quadratic interpolation, or secant if only two points are distinct
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2. * xm * s, 1. - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2. * xm * q * (q - r) - (b - a) * (r - 1.)),
+                    (q - 1.) * (r - 1.) * (s - 1.),
+                )
+            };
+            if p > 0. {
+                q = -q;
+            }
+            p = p.abs();
+
+            if 2. * p < (3. * xm * q - (tol1 * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = xm;
+                e = d;
+            }
+        } else {
+            d = xm;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+        fb = f(b)?;
+    }
+
+    Ok((b, fb))
+}
+
+// Brent's minimizer for a bracket ax < bx < cx with f(bx) = fbx below f(ax) and f(cx)
+fn brent_min<F>(
+    f: &mut F,
+    ax: f64,
+    bx: f64,
+    cx: f64,
+    fbx: f64,
+    tol: f64,
+) -> Result<(f64, f64), SweError>
+where
+    F: FnMut(f64) -> Result<f64, SweError>,
+{
+    const CGOLD: f64 = 0.381_966_011_250_105;
+
+    let (mut a, mut b) = (ax.min(cx), ax.max(cx));
+    let (mut x, mut w, mut v) = (bx, bx, bx);
+    let (mut fx, mut fw, mut fv) = (fbx, fbx, fbx);
+    let (mut d, mut e): (f64, f64) = (0., 0.);
+
+    for _ in 0..MAX_ITERATIONS {
+        let xm = 0.5 * (a + b);
+        let tol1 = 0.5 * tol + f64::EPSILON * x.abs();
+        let tol2 = 2. * tol1;
+        if (x - xm).abs() <= tol2 - 0.5 * (b - a) {
+            return Ok((x, fx));
+        }
+
+        let mut golden = true;
+        if e.abs() > tol1 {
+            // parabolic step
+            let r = (x - w) * (fx - fv);
+            let mut q = (x - v) * (fx - fw);
+            let mut p = (x - v) * q - (x - w) * r;
+            q = 2. * (q - r);
+            if q > 0. {
+                p = -p;
+            }
+            q = q.abs();
+
+            if p.abs() < (0.5 * q * e).abs() && p > q * (a - x) && p < q * (b - x) {
+                e = d;
+                d = p / q;
+                let u = x + d;
+                if u - a < tol2 || b - u < tol2 {
+                    d = tol1.copysign(xm - x);
+                }
+                golden = false;
+            }
+        }
+        if golden {
+            e = if x >= xm { a - x } else { b - x };
+            d = CGOLD * e;
+        }
+
+        let u = if d.abs() >= tol1 { x + d } else { x + tol1.copysign(d) };
+        let fu = f(u)?;
+
+        if fu <= fx {
+            if u >= x {
+                a = x;
+            } else {
+                b = x;
+            }
+            v = w;
+            fv = fw;
+            w = x;
+            fw = fx;
+            x = u;
+            fx = fu;
+        } else {
+            if u < x {
+                a = u;
+            } else {
+                b = u;
+            }
+            if fu <= fw || w == x {
+                v = w;
+                fv = fw;
+                w = u;
+                fw = fu;
+            } else if fu <= fv || v == x || v == w {
+                v = u;
+                fv = fu;
+            }
+        }
+    }
+
+    Ok((x, fx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn solver_finds_roots_and_extrema_of_sine() {
+        let events = Solver::new(0.5)
+            .precision(1e-10)
+            .events(|t| Ok(t.sin()), 0.1, 10.)
+            .unwrap();
+
+        let kinds: Vec<EventKind> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Maximum,
+                EventKind::Descending,
+                EventKind::Minimum,
+                EventKind::Ascending,
+                EventKind::Maximum,
+                EventKind::Descending,
+            ]
+        );
+        assert!((events[0].tjd - PI / 2.).abs() < 1e-6);
+        assert!((events[0].value - 1.).abs() < 1e-9);
+        assert!((events[3].tjd - 2. * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solver_finds_new_moon() {
+        // new moon 2002-01-13 13:29 UT, the difference wraps every synodic month
+        let f = longitude_difference(Body::Moon, Body::Sun, 0, 0.);
+        let roots = Solver::new(1.).angular(true).roots(f, 2452275.5, 2452305.5).unwrap();
+
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].kind, EventKind::Ascending);
+        assert!((roots[0].tjd - 2452288.062).abs() < 0.01);
+    }
+
+    #[test]
+    fn altitude_with_equatorial_positions() {
+        let greenwich = GeoLocation::new(0., 51.48, 0.);
+        let ecliptic = altitude(Body::Sun, greenwich, 0, 0.)(2459751.5).unwrap();
+        let equatorial =
+            altitude(Body::Sun, greenwich, raw::SEFLG_EQUATORIAL as i32, 0.)(2459751.5).unwrap();
+        assert!((ecliptic - equatorial).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::error::SweError;
+use crate::position::Position;
+use crate::raw;
+use crate::solver::normalize_angle;
+use crate::tuple_result;
+
+const J2000: f64 = 2451545.0;
+
+// What swe_fixstar2 says for a number past the last star of the file: "error, swe_fixstar():
+// sequential fixstar number 1235 is not available"
+const PAST_THE_LAST_STAR: &str = "is not available";
+
+// Mean equatorial coordinates at J2000, without nutation, aberration and light deflection
+const J2000_FLAGS: u32 = raw::SEFLG_SWIEPH
+    | raw::SEFLG_J2000
+    | raw::SEFLG_EQUATORIAL
+    | raw::SEFLG_NONUT
+    | raw::SEFLG_NOABERR
+    | raw::SEFLG_NOGDEFL;
+
+// An entry of the fixed star file (sefstars.txt) the library is using
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedStar {
+    // Position in the file counting stars only, for stars from star_catalogue. Only valid
+    // until the star file changes.
+    pub number: Option<usize>,
+    // Traditional name, empty for stars that only have a nomenclature
+    pub name: String,
+    // Bayer or Flamsteed designation, e.g. "alTau"
+    pub nomenclature: String,
+    pub magnitude: f64,
+    // Right ascension and declination at J2000 in degrees
+    pub ra_j2000: f64,
+    pub dec_j2000: f64,
+}
+
+impl FixedStar {
+    // The IAU abbreviation at the end of the nomenclature, e.g. "Tau" for "alTau"
+    pub fn constellation(&self) -> Option<&str> {
+        let n = self.nomenclature.len();
+        let abbreviation = self.nomenclature.get(n.checked_sub(3)?..)?;
+        let mut chars = abbreviation.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_uppercase() && chars.all(|c| c.is_ascii_alphabetic()) => {
+                Some(abbreviation)
+            }
+            _ => None,
+        }
+    }
+
+    // What swe_fixstar2 looks the star up by: the number if known, else the nomenclature,
+    // which unlike names does not match by prefix
+    fn search_key(&self) -> String {
+        match self.number {
+            Some(number) => number.to_string(),
+            None if !self.nomenclature.is_empty() => format!(",{}", self.nomenclature),
+            None => self.name.clone(),
+        }
+    }
+
+    // Position at tjd_ut (UT) with swe_fixstar2_ut
+    pub fn position_ut(&self, tjd_ut: f64, iflag: i32) -> Result<Position, SweError> {
+        let (_, _, xx) =
+            unsafe { tuple_result::swe_fixstar2_ut(&self.search_key(), tjd_ut, iflag)? };
+        Ok(Position::from(xx))
+    }
+}
+
+fn read_star(star: &str, number: Option<usize>) -> Result<FixedStar, SweError> {
+    let (_, found, xx) = unsafe { tuple_result::swe_fixstar2(star, J2000, J2000_FLAGS as i32)? };
+    let (_, _, magnitude) = unsafe { tuple_result::swe_fixstar2_mag(star)? };
+
+    // "name,nomenclature", padded with nul characters
+    let found = found.trim_end_matches('\0');
+    let (name, nomenclature) = found.split_once(',').unwrap_or((found, ""));
+    Ok(FixedStar {
+        number,
+        name: name.trim().to_string(),
+        nomenclature: nomenclature.trim().to_string(),
+        magnitude,
+        ra_j2000: xx[0],
+        dec_j2000: xx[1],
+    })
+}
+
+// Looks up a star by name, or by nomenclature with a leading comma (",alTau"). Names
+// match by prefix as in swe_fixstar2. The library does not return the number of the star.
+pub fn fixed_star(star: &str) -> Result<FixedStar, SweError> {
+    read_star(star, None)
+}
+
+// Every star in the star file in file order
+pub fn star_catalogue() -> StarCatalogue {
+    StarCatalogue {
+        number: 1,
+        done: false,
+    }
+}
+
+pub struct StarCatalogue {
+    number: usize,
+    done: bool,
+}
+
+impl Iterator for StarCatalogue {
+    type Item = Result<FixedStar, SweError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match read_star(&self.number.to_string(), Some(self.number)) {
+            Ok(star) => {
+                self.number += 1;
+                Some(Ok(star))
+            }
+            Err(SweError::Library(_, serr))
+                if self.number > 1 && serr.contains(PAST_THE_LAST_STAR) =>
+            {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StarMatch {
+    pub star: FixedStar,
+    // Position at the time of the search, with its iflag
+    pub position: Position,
+}
+
+// Filters over the star catalogue, e.g. the bright stars within a degree of a natal
+// point:
+//     StarSearch::new().brighter_than(2.).near_longitude(lon, 1.).search(tjd_ut, iflag)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StarSearch {
+    name_prefix: Option<String>,
+    max_magnitude: Option<f64>,
+    longitude: Option<(f64, f64)>,
+}
+
+impl StarSearch {
+    pub fn new() -> Self {
+        StarSearch::default()
+    }
+
+    // Traditional name starting with prefix, ignoring case
+    pub fn name_prefix(mut self, prefix: &str) -> Self {
+        self.name_prefix = Some(prefix.to_lowercase());
+        self
+    }
+
+    // Magnitude at most magnitude
+    pub fn brighter_than(mut self, magnitude: f64) -> Self {
+        self.max_magnitude = Some(magnitude);
+        self
+    }
+
+    // Longitude (in the coordinates of the search's iflag) within orb degrees of
+    // longitude
+    pub fn near_longitude(mut self, longitude: f64, orb: f64) -> Self {
+        self.longitude = Some((longitude, orb));
+        self
+    }
+
+    fn matches_entry(&self, star: &FixedStar) -> bool {
+        let name = match &self.name_prefix {
+            Some(prefix) => star.name.to_lowercase().starts_with(prefix.as_str()),
+            None => true,
+        };
+        let magnitude = match self.max_magnitude {
+            Some(max) => star.magnitude <= max,
+            None => true,
+        };
+        name && magnitude
+    }
+
+    // Stars passing every filter, with their positions at tjd_ut (UT)
+    pub fn search(&self, tjd_ut: f64, iflag: i32) -> Result<Vec<StarMatch>, SweError> {
+        let mut found = Vec::new();
+        for star in star_catalogue() {
+            let star = star?;
+            if !self.matches_entry(&star) {
+                continue;
+            }
+
+            let position = star.position_ut(tjd_ut, iflag)?;
+            if let Some((longitude, orb)) = self.longitude {
+                if normalize_angle(position.longitude - longitude).abs() > orb {
+                    continue;
+                }
+            }
+            found.push(StarMatch { star, position });
+        }
+
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ephe_path;
+
+    #[test]
+    fn aldebaran_in_the_catalogue() {
+        let _ephe = ephe_path();
+
+        let stars: Vec<FixedStar> = star_catalogue().collect::<Result<_, _>>().unwrap();
+        assert!(stars.len() > 1000);
+        // the iterator stops on the error for the number after the last star
+        match read_star(&(stars.len() + 1).to_string(), None) {
+            Err(SweError::Library(_, serr)) => {
+                assert!(serr.contains(PAST_THE_LAST_STAR), "{}", serr)
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let aldebaran = stars.iter().find(|s| s.name == "Aldebaran").unwrap();
+        assert_eq!(aldebaran.nomenclature, "alTau");
+        assert_eq!(aldebaran.constellation(), Some("Tau"));
+        assert!((aldebaran.magnitude - 0.86).abs() < 0.1);
+        // 4h35m55.2s +16d30m33s
+        assert!((aldebaran.ra_j2000 - 68.980).abs() < 0.01);
+        assert!((aldebaran.dec_j2000 - 16.509).abs() < 0.01);
+
+        let found = fixed_star(",alTau").unwrap();
+        assert_eq!(found.number, None);
+        assert_eq!(found.name, "Aldebaran");
+        assert_eq!(
+            found.position_ut(J2000, 0).unwrap(),
+            aldebaran.position_ut(J2000, 0).unwrap()
+        );
+        assert!(fixed_star(&"a".repeat(300)).is_err());
+    }
+
+    #[test]
+    fn search_by_name_magnitude_and_longitude() {
+        let _ephe = ephe_path();
+
+        let alde = StarSearch::new()
+            .name_prefix("alde")
+            .search(J2000, 0)
+            .unwrap();
+        assert!(alde.iter().any(|m| m.star.name == "Aldebaran"));
+
+        // Regulus was at 29 Leo 50 in 2000
+        let near = StarSearch::new()
+            .brighter_than(2.)
+            .near_longitude(149.83, 1.)
+            .search(J2000, 0)
+            .unwrap();
+        assert!(near.iter().any(|m| m.star.name == "Regulus"));
+        for m in &near {
+            assert!(m.star.magnitude <= 2.);
+            assert!((m.position.longitude - 149.83).abs() <= 1.);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::body::Body;
+use crate::error::SweError;
+use crate::position::calc_ut;
+use crate::raw;
+use crate::solver::{longitude_minus, longitude_speed, EventKind, Solver};
+
+// Stations of the planets, asteroids and mean points are at least a couple of weeks apart,
+// a one day step can not skip over one. The true node and the osculating apogee wobble
+// back and forth within days and are not supported.
+const STEP: f64 = 1.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationKind {
+    // Speed turns negative, the body starts moving backwards
+    Retrograde,
+    // Speed turns positive again
+    Direct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Station {
+    pub kind: StationKind,
+    pub tjd_ut: f64,
+    pub longitude: f64,
+}
+
+// One retrograde loop. The pre-retrograde shadow begins when the body first reaches the
+// longitude where it will station direct, the post-retrograde shadow ends when it passes
+// the longitude where it stationed retrograde. The shadow times are None if they fall
+// outside the bracket searched around the station, twice the retrograde motion plus 30
+// days before the retrograde station and after the direct one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetrogradePeriod {
+    pub shadow_start: Option<f64>,
+    pub station_retrograde: Station,
+    pub station_direct: Station,
+    pub shadow_end: Option<f64>,
+}
+
+// All stations of a body between start and end (UT). iflag is passed to swe_calc_ut,
+// SEFLG_SPEED is always added. Returns SweError::InvalidArgument for Body::TrueNode and
+// Body::OscuApogee.
+pub fn stations(body: Body, start: f64, end: f64, iflag: i32) -> Result<Vec<Station>, SweError> {
+    if matches!(body, Body::TrueNode | Body::OscuApogee) {
+        return Err(SweError::InvalidArgument(format!(
+            "stations of {:?} are too close together to search",
+            body
+        )));
+    }
+
+    let iflag = iflag | raw::SEFLG_SPEED as i32;
+    let roots = Solver::new(STEP).roots(longitude_speed(body, iflag), start, end)?;
+
+    roots
+        .into_iter()
+        .map(|root| {
+            let kind = match root.kind {
+                EventKind::Descending => StationKind::Retrograde,
+                _ => StationKind::Direct,
+            };
+            let longitude = calc_ut(body, root.tjd, iflag)?.longitude;
+
+            Ok(Station { kind, tjd_ut: root.tjd, longitude })
+        })
+        .collect()
+}
+
+// Retrograde periods whose retrograde station is between start and end (UT), with their
+// shadow periods
+pub fn retrograde_periods(
+    body: Body,
+    start: f64,
+    end: f64,
+    iflag: i32,
+) -> Result<Vec<RetrogradePeriod>, SweError> {
+    // the direct station of the last period can be after end
+    let all = stations(body, start, end + 366., iflag)?;
+    let mut periods = Vec::new();
+
+    for pair in all.windows(2) {
+        let (sr, sd) = (pair[0], pair[1]);
+        if sr.kind != StationKind::Retrograde || sr.tjd_ut > end {
+            continue;
+        }
+
+        // shadows take about as long as the retrograde motion itself
+        let window = 2. * (sd.tjd_ut - sr.tjd_ut) + 30.;
+        let solver = Solver::new(STEP).angular(true);
+
+        let entries = solver.roots(
+            longitude_minus(body, iflag, sd.longitude),
+            sr.tjd_ut - window,
+            sr.tjd_ut,
+        )?;
+        let shadow_start = entries
+            .iter()
+            .rev()
+            .find(|e| e.kind == EventKind::Ascending)
+            .map(|e| e.tjd);
+
+        let exits = solver.roots(
+            longitude_minus(body, iflag, sr.longitude),
+            sd.tjd_ut,
+            sd.tjd_ut + window,
+        )?;
+        let shadow_end = exits
+            .iter()
+            .find(|e| e.kind == EventKind::Ascending)
+            .map(|e| e.tjd);
+
+        periods.push(RetrogradePeriod {
+            shadow_start,
+            station_retrograde: sr,
+            station_direct: sd,
+            shadow_end,
+        });
+    }
+
+    Ok(periods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mercury_stations_2002() {
+        // 2002: Jan 18, May 15 and Sep 14 retrograde; Feb 8, Jun 8 and Oct 6 direct
+        let s = stations(Body::Mercury, 2452275.5, 2452640.5, 0).unwrap();
+
+        assert_eq!(s.len(), 6);
+        assert_eq!(s[0].kind, StationKind::Retrograde);
+        assert_eq!(s[1].kind, StationKind::Direct);
+        assert!((s[0].tjd_ut - 2452293.0).abs() < 1.);
+        assert!((s[1].tjd_ut - 2452313.5).abs() < 1.);
+
+        let err = stations(Body::TrueNode, 2452275.5, 2452640.5, 0).unwrap_err();
+        assert!(matches!(err, SweError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn mercury_shadow_period() {
+        let periods = retrograde_periods(Body::Mercury, 2452275.5, 2452300.5, 0).unwrap();
+        assert_eq!(periods.len(), 1);
+
+        let p = periods[0];
+        let shadow_start = p.shadow_start.unwrap();
+        let shadow_end = p.shadow_end.unwrap();
+        assert!(shadow_start < p.station_retrograde.tjd_ut);
+        assert!(p.station_direct.tjd_ut < shadow_end);
+
+        let at_start = calc_ut(Body::Mercury, shadow_start, 0).unwrap();
+        assert!((at_start.longitude - p.station_direct.longitude).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::tuple_result;
+
+// The ephe directory of the library submodule, with sefstars.txt
+pub(crate) const EPHE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/libswisseph/ephe");
+
+// EPHE_PATH set for one test. Dropping it calls swe_close, which returns the library
+// state of the thread to its defaults, so later tests don't depend on it.
+pub(crate) struct EphePath(());
+
+pub(crate) fn ephe_path() -> EphePath {
+    unsafe { tuple_result::swe_set_ephe_path(EPHE_PATH) };
+    EphePath(())
+}
+
+impl Drop for EphePath {
+    fn drop(&mut self) {
+        unsafe { tuple_result::swe_close() };
+    }
+}
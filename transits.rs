@@ -0,0 +1,285 @@
+use crate::aspect::{AspectDef, AspectSet};
+use crate::body::Body;
+use crate::cache::PositionCache;
+use crate::chart::Chart;
+use crate::error::SweError;
+use crate::solver::{normalize_angle, EventKind, Solver};
+
+// A point of the natal chart that can be transited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NatalPoint {
+    Body(Body),
+    Ascendant,
+    Mc,
+}
+
+// Every body of the chart followed by the ascendant and MC, with their longitudes
+pub fn natal_points(chart: &Chart) -> Vec<(NatalPoint, f64)> {
+    let mut points: Vec<(NatalPoint, f64)> = chart
+        .bodies
+        .iter()
+        .map(|b| (NatalPoint::Body(b.body), b.position.longitude))
+        .collect();
+    points.push((NatalPoint::Ascendant, chart.houses.angles.ascendant));
+    points.push((NatalPoint::Mc, chart.houses.angles.mc));
+    points
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitOptions {
+    pub bodies: Vec<Body>,
+    pub aspects: AspectSet,
+    // Sampling step in days. Orb entries, exits and exact hits closer together than this
+    // can be missed; half a day is enough for the Moon with orbs of a degree or more.
+    pub step: f64,
+}
+
+impl Default for TransitOptions {
+    // Sun to Pluto with the major aspects
+    fn default() -> Self {
+        TransitOptions {
+            bodies: Body::PLANETS.to_vec(),
+            aspects: AspectSet::major(),
+            step: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExactHit {
+    pub tjd_ut: f64,
+    // The transiting body is retrograde, i.e. this is a re-contact
+    pub retrograde: bool,
+}
+
+// One stay of a transiting body within orb of an aspect to a natal point. A retrograde
+// loop inside the orb gives up to three exact hits; if the body leaves the orb between
+// passes, each pass is a transit of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transit {
+    pub transiting: Body,
+    pub natal: NatalPoint,
+    pub aspect: AspectDef,
+    // Transiting minus natal longitude at the exact aspect, +angle or -angle
+    pub separation: f64,
+    // None if already within orb at the start of the range
+    pub enters_orb: Option<f64>,
+    pub exact: Vec<ExactHit>,
+    // None if still within orb at the end of the range
+    pub leaves_orb: Option<f64>,
+}
+
+// All transits of the option bodies to the natal points of chart between start and end
+// (UT), ordered by the time they enter orb. Positions are calculated with the flags,
+// sidereal mode and topocentric location of the chart, so a sidereal chart gets sidereal
+// transits. The library's previous mode and location are set again afterwards.
+pub fn transits(
+    chart: &Chart,
+    start: f64,
+    end: f64,
+    options: &TransitOptions,
+) -> Result<Vec<Transit>, SweError> {
+    chart.with_library_state(|| search_transits(chart, start, end, options))
+}
+
+fn search_transits(
+    chart: &Chart,
+    start: f64,
+    end: f64,
+    options: &TransitOptions,
+) -> Result<Vec<Transit>, SweError> {
+    let mut cache = PositionCache::new(chart.iflag);
+    let points = natal_points(chart);
+    let mut transits = Vec::new();
+
+    for &body in &options.bodies {
+        for &(natal, natal_longitude) in &points {
+            for def in &options.aspects.aspects {
+                let angle = def.kind.angle();
+                let mut separations = vec![angle];
+                if angle > 0. && angle < 180. {
+                    separations.push(-angle);
+                }
+
+                for separation in separations {
+                    let target = natal_longitude + separation;
+                    transits.extend(search(
+                        &mut cache,
+                        body,
+                        natal,
+                        *def,
+                        separation,
+                        target,
+                        start,
+                        end,
+                        options.step,
+                    )?);
+                }
+            }
+        }
+    }
+
+    transits.sort_by(|a, b| {
+        let a = a.enters_orb.unwrap_or(start);
+        let b = b.enters_orb.unwrap_or(start);
+        a.total_cmp(&b)
+    });
+    Ok(transits)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    Enter,
+    Exact,
+    Leave,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search(
+    cache: &mut PositionCache,
+    body: Body,
+    natal: NatalPoint,
+    aspect: AspectDef,
+    separation: f64,
+    target: f64,
+    start: f64,
+    end: f64,
+    step: f64,
+) -> Result<Vec<Transit>, SweError> {
+    let solver = Solver::new(step);
+    let mut deviation = |t: f64| -> Result<f64, SweError> {
+        Ok(normalize_angle(
+            cache.position_ut(body, t)?.longitude - target,
+        ))
+    };
+
+    // |deviation| - orb is continuous and negative within orb
+    let outside = solver.roots(|t| Ok(deviation(t)?.abs() - aspect.orb), start, end)?;
+    let exact = solver.angular(true).roots(&mut deviation, start, end)?;
+    let inside_at_start = deviation(start)?.abs() <= aspect.orb;
+
+    let mut marks: Vec<(f64, Mark)> = outside
+        .iter()
+        .map(|e| match e.kind {
+            EventKind::Descending => (e.tjd, Mark::Enter),
+            _ => (e.tjd, Mark::Leave),
+        })
+        .chain(exact.iter().map(|e| (e.tjd, Mark::Exact)))
+        .collect();
+    marks.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let new_transit = |enters_orb| Transit {
+        transiting: body,
+        natal,
+        aspect,
+        separation,
+        enters_orb,
+        exact: Vec::new(),
+        leaves_orb: None,
+    };
+
+    let mut transits = Vec::new();
+    let mut current = match inside_at_start {
+        true => Some(new_transit(None)),
+        false => None,
+    };
+    for (tjd_ut, mark) in marks {
+        match mark {
+            Mark::Enter => current = Some(new_transit(Some(tjd_ut))),
+            Mark::Exact => {
+                let retrograde = cache.position_ut(body, tjd_ut)?.is_retrograde();
+                current
+                    .get_or_insert_with(|| new_transit(None))
+                    .exact
+                    .push(ExactHit { tjd_ut, retrograde });
+            }
+            Mark::Leave => {
+                if let Some(mut transit) = current.take() {
+                    transit.leaves_orb = Some(tjd_ut);
+                    transits.push(transit);
+                }
+            }
+        }
+    }
+    transits.extend(current);
+
+    Ok(transits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aspect::AspectKind;
+    use crate::chart::{ChartOptions, Zodiac};
+    use crate::location::GeoLocation;
+    use crate::raw;
+
+    #[test]
+    fn sun_conjunct_natal_sun_once_a_year() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let natal = Chart::new(2452275.5, zurich, &ChartOptions::default()).unwrap();
+        let options = TransitOptions {
+            bodies: vec![Body::Sun],
+            aspects: AspectSet::new().with(AspectKind::Conjunction, 1.),
+            step: 0.5,
+        };
+
+        let all = transits(&natal, 2452275.5 + 180., 2452275.5 + 540., &options).unwrap();
+        let to_sun: Vec<&Transit> = all
+            .iter()
+            .filter(|t| t.natal == NatalPoint::Body(Body::Sun))
+            .collect();
+
+        assert_eq!(to_sun.len(), 1);
+        let t = to_sun[0];
+        // about a degree a day
+        let (enters, leaves) = (t.enters_orb.unwrap(), t.leaves_orb.unwrap());
+        assert!((leaves - enters - 2.).abs() < 0.1);
+        assert_eq!(t.exact.len(), 1);
+        assert!(enters < t.exact[0].tjd_ut && t.exact[0].tjd_ut < leaves);
+    }
+
+    #[test]
+    fn mars_retrograde_recontact() {
+        // Mars was retrograde from late July to late September 2003, between 10 and 0 Pisces
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let mut natal = Chart::new(2452275.5, zurich, &ChartOptions::default()).unwrap();
+        natal.bodies.truncate(0);
+        natal.houses.angles.ascendant = 335.;
+        natal.houses.angles.mc = 200.;
+
+        let options = TransitOptions {
+            bodies: vec![Body::Mars],
+            aspects: AspectSet::new().with(AspectKind::Conjunction, 1.),
+            step: 1.,
+        };
+        let all = transits(&natal, 2452640.5, 2453005.5, &options).unwrap();
+        let hits: Vec<ExactHit> = all.iter().flat_map(|t| t.exact.clone()).collect();
+
+        assert_eq!(hits.len(), 3);
+        assert!(!hits[0].retrograde && hits[1].retrograde && !hits[2].retrograde);
+    }
+
+    #[test]
+    fn sidereal_transits_keep_the_mode_of_the_chart() {
+        let zurich = GeoLocation::new(8.55, 47.37, 400.);
+        let sidereal = |sid_mode: u32| ChartOptions {
+            zodiac: Zodiac::Sidereal(sid_mode as i32),
+            bodies: vec![Body::Sun],
+            ..ChartOptions::default()
+        };
+        let options = TransitOptions {
+            bodies: vec![Body::Sun],
+            aspects: AspectSet::new().with(AspectKind::Conjunction, 1.),
+            step: 0.5,
+        };
+
+        let lahiri = Chart::new(2452275.5, zurich, &sidereal(raw::SE_SIDM_LAHIRI)).unwrap();
+        let before = transits(&lahiri, 2452275.5 + 180., 2452275.5 + 540., &options).unwrap();
+        // sets the Fagan/Bradley mode, about 50' from Lahiri
+        Chart::new(2452275.5, zurich, &sidereal(raw::SE_SIDM_FAGAN_BRADLEY)).unwrap();
+        let after = transits(&lahiri, 2452275.5 + 180., 2452275.5 + 540., &options).unwrap();
+
+        assert_eq!(before, after);
+    }
+}
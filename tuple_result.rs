@@ -1,3 +1,6 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 // Simple methods don't require any error handling, error strings, or modifing data in place
 pub mod simple;
 pub use simple::*;
@@ -8,3 +11,57 @@ pub use simple::*;
 pub mod create;
 pub use create::*;
 
+// Bumped by swe_set_ephe_path, swe_close, swe_set_sid_mode and swe_set_topo, which change
+// the positions the library returns for the same arguments. Caches compare it to the value
+// they were filled with, so stale positions are never served. Calls made directly through
+// raw or safe are not seen.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+fn bump_generation() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+// The arguments of the last swe_set_sid_mode and swe_set_topo of this thread, which the
+// library keeps per thread too. swe_close resets them, calls through raw or safe are not
+// seen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Settings {
+    sid_mode: Option<(i32, f64, f64)>,
+    topo: Option<(f64, f64, f64)>,
+}
+
+thread_local! {
+    static SETTINGS: Cell<Settings> = Cell::new(Settings::default());
+}
+
+impl Settings {
+    pub(crate) fn current() -> Settings {
+        SETTINGS.with(Cell::get)
+    }
+
+    // Sets them again, e.g. after swe_close. A sidereal mode that was never set is set to
+    // the library's default, Fagan/Bradley; a topocentric location stays as it is.
+    pub(crate) unsafe fn restore(self) {
+        let (sid_mode, t0, ayan_t0) = self.sid_mode.unwrap_or((
+            crate::raw::SE_SIDM_FAGAN_BRADLEY as i32,
+            0.,
+            0.,
+        ));
+        swe_set_sid_mode(sid_mode, t0, ayan_t0);
+        if let Some((geolon, geolat, geoalt)) = self.topo {
+            swe_set_topo(geolon, geolat, geoalt);
+        }
+    }
+}
+
+fn update_settings(f: impl FnOnce(&mut Settings)) {
+    SETTINGS.with(|settings| {
+        let mut current = settings.get();
+        f(&mut current);
+        settings.set(current);
+    });
+}
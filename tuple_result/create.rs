@@ -1,5 +1,6 @@
 use crate::raw;
 use std::ffi::CStr;
+use std::os::raw::c_char;
 use crate::types::*;
 use crate::util::*;
 
@@ -15,22 +16,22 @@ use crate::util::*;
 
 pub unsafe fn swe_heliacal_ut(
     tjdstart_ut: f64,
-    geopos: *mut f64,
-    datm: *mut f64,
-    dobs: *mut f64,
+    mut geopos: [f64; 3],
+    mut datm: [f64; 4],
+    mut dobs: [f64; 6],
     object_name: &str,
     type_event: i32,
     iflag: i32,
 ) -> Result<(i32, [f64;50]), (i32, String)> {
-    let mut object_name_buffer = new_max_buffer_from_str(object_name);
+    let mut object_name_buffer = new_star_buffer(object_name)?;
     // result: array of at least 50 doubles, of which 3 are used at the moment
     let mut dret: [f64;50] = [0.;50];
     let mut serr = new_max_buffer();
     let ret_code = raw::swe_heliacal_ut(
         tjdstart_ut,
-        geopos,
-        datm,
-        dobs,
+        geopos.as_mut_ptr(),
+        datm.as_mut_ptr(),
+        dobs.as_mut_ptr(),
         object_name_buffer.as_mut_ptr(),
         type_event,
         iflag,
@@ -78,22 +79,22 @@ pub unsafe fn swe_heliacal_ut(
 // '29=MSk        [-]
 pub unsafe fn swe_heliacal_pheno_ut(
     tjd_ut: f64,
-    geopos: *mut f64,
-    datm: *mut f64,
-    dobs: *mut f64,
+    mut geopos: [f64; 3],
+    mut datm: [f64; 4],
+    mut dobs: [f64; 6],
     object_name: &str,
     type_event: i32,
     helflag: i32,
 ) -> Result<(i32, [f64;50]), (i32, String)> {
     let mut serr = new_max_buffer();
-    let mut object_name_buffer = new_max_buffer_from_str(object_name);
+    let mut object_name_buffer = new_star_buffer(object_name)?;
     // return array, declare array of 50 doubles 
     let mut darr: [f64;50] = [0.;50];
     let ret_code = raw::swe_heliacal_pheno_ut(
         tjd_ut,
-        geopos,
-        datm,
-        dobs,
+        geopos.as_mut_ptr(),
+        datm.as_mut_ptr(),
+        dobs.as_mut_ptr(),
         object_name_buffer.as_mut_ptr(),
         type_event,
         helflag,
@@ -119,14 +120,14 @@ pub unsafe fn swe_heliacal_pheno_ut(
 //dret[7]: magnitude of object.
 pub unsafe fn swe_vis_limit_mag(
     tjdut: f64,
-    geopos: *mut f64,
-    datm: *mut f64,
-    dobs: *mut f64,
+    mut geopos: [f64; 3],
+    mut datm: [f64; 4],
+    mut dobs: [f64; 6],
     object_name: &str,
     helflag: i32,
 ) -> Result<(i32, [f64;8]), (i32, String)> {
     let mut serr = new_max_buffer();
-    let mut object_name_buffer = new_max_buffer_from_str(object_name);
+    let mut object_name_buffer = new_star_buffer(object_name)?;
 
     let mut dret: [f64;8] = [0.;8];
 
@@ -138,61 +139,67 @@ pub unsafe fn swe_vis_limit_mag(
     //     &2   OK, near limit photopic/scotopic vision.
     let ret_code = raw::swe_vis_limit_mag(
         tjdut,
-        geopos,
-        datm,
-        dobs,
+        geopos.as_mut_ptr(),
+        datm.as_mut_ptr(),
+        dobs.as_mut_ptr(),
         object_name_buffer.as_mut_ptr(),
         helflag,
         dret.as_mut_ptr(),
         serr.as_mut_ptr(),
     );
 
-    if ret_code < RAW_OK {
+    if ret_code == RAW_ERR {
         return Err((ret_code, buffer_to_string(serr)))
     }
 
     Ok((ret_code, dret))
 }
 
-// TODO:
+// dret[0] heliacal angle, dret[1] minimal topocentric arcus visionis, dret[2] altitude of
+// the Sun
 pub unsafe fn swe_heliacal_angle(
     tjdut: f64,
-    dgeo: *mut f64,
-    datm: *mut f64,
-    dobs: *mut f64,
+    mut dgeo: [f64; 3],
+    mut datm: [f64; 4],
+    mut dobs: [f64; 6],
     helflag: i32,
     mag: f64,
     azi_obj: f64,
     azi_sun: f64,
     azi_moon: f64,
     alt_moon: f64,
-    dret: *mut f64,
-) -> i32 {
+//    dret: *mut f64,
+) -> Result<(i32, [f64; 3]), (i32, String)> {
     let mut serr = new_max_buffer();
+    let mut dret: [f64; 3] = [0.; 3];
     let ret_code = raw::swe_heliacal_angle(
         tjdut,
-        dgeo,
-        datm,
-        dobs,
+        dgeo.as_mut_ptr(),
+        datm.as_mut_ptr(),
+        dobs.as_mut_ptr(),
         helflag,
         mag,
         azi_obj,
         azi_sun,
         azi_moon,
         alt_moon,
-        dret,
+        dret.as_mut_ptr(),
         serr.as_mut_ptr(),
     );
 
-    ret_code
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, dret))
 }
 
-// TODO:
+// dret: minimal topocentric arcus visionis
 pub unsafe fn swe_topo_arcus_visionis(
     tjdut: f64,
-    dgeo: *mut f64,
-    datm: *mut f64,
-    dobs: *mut f64,
+    mut dgeo: [f64; 3],
+    mut datm: [f64; 4],
+    mut dobs: [f64; 6],
     helflag: i32,
     mag: f64,
     azi_obj: f64,
@@ -200,14 +207,15 @@ pub unsafe fn swe_topo_arcus_visionis(
     azi_sun: f64,
     azi_moon: f64,
     alt_moon: f64,
-    dret: *mut f64,
-) -> (i32, i32) {
+//    dret: *mut f64,
+) -> Result<(i32, f64), (i32, String)> {
     let mut serr = new_max_buffer();
+    let mut dret: f64 = 0.;
     let ret_code = raw::swe_topo_arcus_visionis(
         tjdut,
-        dgeo,
-        datm,
-        dobs,
+        dgeo.as_mut_ptr(),
+        datm.as_mut_ptr(),
+        dobs.as_mut_ptr(),
         helflag,
         mag,
         azi_obj,
@@ -215,10 +223,15 @@ pub unsafe fn swe_topo_arcus_visionis(
         azi_sun,
         azi_moon,
         alt_moon,
-        dret,
+        &mut dret,
         serr.as_mut_ptr(),
     );
-    (ret_code, ret_code)
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, dret))
 }
 
 // TODO
@@ -326,204 +339,194 @@ pub unsafe fn swe_calc_ut(
 //}
 //
 //
-//// Return value: double jx = time of next crossing, in Ephemeris Time or Universal Time.
-//pub fn swe_solcross(
-//    x2cross: f64,
-//    jd_et: f64,
-//    flag: i32,
-//) -> Result<f64, (f64, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_solcross(
-//            x2cross,
-//            jd_et,
-//            flag,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_et {
-//            return Err((jx, buffer_to_string(serr)))
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//pub fn swe_solcross_ut(
-//    x2cross: f64,
-//    jd_ut: f64,
-//    flag: i32,
-//) -> Result<f64, (f64, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_solcross_ut(
-//            x2cross,
-//            jd_ut,
-//            flag,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_ut {
-//            return Err((jx, buffer_to_string(serr)))
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//pub fn swe_mooncross(
-//    x2cross: f64,
-//    jd_et: f64,
-//    flag: i32,
-//) -> Result<f64, (f64, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_mooncross(
-//            x2cross,
-//            jd_et,
-//            flag,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_et {
-//            return Err((jx, buffer_to_string(serr)))
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//pub fn swe_mooncross_ut(
-//    x2cross: f64,
-//    jd_ut: f64,
-//    flag: i32,
-//) -> Result<f64, (f64, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_mooncross_ut(
-//            x2cross,
-//            jd_ut,
-//            flag,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_ut {
-//            return Err((jx, buffer_to_string(serr)));
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//pub fn swe_mooncross_node(
-//    jd_et: f64,
-//    flag: i32,
+// Return value: double jx = time of next crossing, in Ephemeris Time or Universal Time.
+pub unsafe fn swe_solcross(
+    x2cross: f64,
+    jd_et: f64,
+    flag: i32,
+) -> Result<f64, (f64, String)> {
+    let mut serr = new_max_buffer();
+    let jx = raw::swe_solcross(
+        x2cross,
+        jd_et,
+        flag,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_et {
+        return Err((jx, buffer_to_string(serr)))
+    }
+
+    Ok(jx)
+}
+
+pub unsafe fn swe_solcross_ut(
+    x2cross: f64,
+    jd_ut: f64,
+    flag: i32,
+) -> Result<f64, (f64, String)> {
+    let mut serr = new_max_buffer();
+    let jx = raw::swe_solcross_ut(
+        x2cross,
+        jd_ut,
+        flag,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_ut {
+        return Err((jx, buffer_to_string(serr)))
+    }
+
+    Ok(jx)
+}
+
+pub unsafe fn swe_mooncross(
+    x2cross: f64,
+    jd_et: f64,
+    flag: i32,
+) -> Result<f64, (f64, String)> {
+    let mut serr = new_max_buffer();
+    let jx = raw::swe_mooncross(
+        x2cross,
+        jd_et,
+        flag,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_et {
+        return Err((jx, buffer_to_string(serr)))
+    }
+
+    Ok(jx)
+}
+
+pub unsafe fn swe_mooncross_ut(
+    x2cross: f64,
+    jd_ut: f64,
+    flag: i32,
+) -> Result<f64, (f64, String)> {
+    let mut serr = new_max_buffer();
+    let jx = raw::swe_mooncross_ut(
+        x2cross,
+        jd_ut,
+        flag,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_ut {
+        return Err((jx, buffer_to_string(serr)));
+    }
+
+    Ok(jx)
+}
+
+// Returns the time of the next crossing of the lunar node and the longitude and
+// latitude of the moon at that time
+pub unsafe fn swe_mooncross_node(
+    jd_et: f64,
+    flag: i32,
 //    xlon: *mut f64,
 //    xlat: *mut f64,
-//) -> Result<f64, (f64, String)>  {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_mooncross_node(
-//            jd_et,
-//            flag,
-//            xlon,
-//            xlat,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_et {
-//            return Err((jx, buffer_to_string(serr)));
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//pub fn swe_mooncross_node_ut(
-//    jd_ut: f64,
-//    flag: i32,
+) -> Result<(f64, f64, f64), (f64, String)>  {
+    let mut serr = new_max_buffer();
+    let mut xlon: f64 = 0.;
+    let mut xlat: f64 = 0.;
+    let jx = raw::swe_mooncross_node(
+        jd_et,
+        flag,
+        &mut xlon,
+        &mut xlat,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_et {
+        return Err((jx, buffer_to_string(serr)));
+    }
+
+    Ok((jx, xlon, xlat))
+}
+
+pub unsafe fn swe_mooncross_node_ut(
+    jd_ut: f64,
+    flag: i32,
 //    xlon: *mut f64,
 //    xlat: *mut f64,
-//) -> Result<f64, (f64, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let jx = raw::swe_mooncross_node_ut(
-//            jd_ut,
-//            flag,
-//            xlon,
-//            xlat,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if jx < jd_ut {
-//            return Err((jx, buffer_to_string(serr)));
-//        }
-//
-//        Ok(jx)
-//    }
-//}
-//
-//// Return value < 0 indicates an error, with error details in string serr 
-//// (unless serr is a NULL pointer).
-//// The crossing time is returned via parameter jx.
-//pub fn swe_helio_cross(
-//    ipl: i32,
-//    x2cross: f64,
-//    jd_et: f64,
-//    iflag: i32,
-//    dir: i32,
-////    jd_cross: *mut f64,
-//) -> Result<(i32, f64), (i32, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let mut jd_cross: f64 = 0.;
-//        let ret_code = raw::swe_helio_cross(
-//            ipl,
-//            x2cross,
-//            jd_et,
-//            iflag,
-//            dir,
-//            &mut jd_cross,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        Ok((ret_code, jd_cross))
-//    }
-//}
-//
-//pub fn swe_helio_cross_ut(
-//    ipl: i32,
-//    x2cross: f64,
-//    jd_ut: f64,
-//    iflag: i32,
-//    dir: i32,
-////    jd_cross: *mut f64,
-//) -> Result<(i32, f64), (i32, String)> {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        let mut jd_cross: f64 = 0.;
-//        let ret_code = raw::swe_helio_cross_ut(
-//            ipl,
-//            x2cross,
-//            jd_ut,
-//            iflag,
-//            dir,
-//            &mut jd_cross,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        Ok((ret_code, jd_cross))
-//    }
-//}
-//
+) -> Result<(f64, f64, f64), (f64, String)> {
+    let mut serr = new_max_buffer();
+    let mut xlon: f64 = 0.;
+    let mut xlat: f64 = 0.;
+    let jx = raw::swe_mooncross_node_ut(
+        jd_ut,
+        flag,
+        &mut xlon,
+        &mut xlat,
+        serr.as_mut_ptr(),
+    );
+
+    if jx < jd_ut {
+        return Err((jx, buffer_to_string(serr)));
+    }
+
+    Ok((jx, xlon, xlat))
+}
+
+// Return value < 0 indicates an error, with error details in string serr 
+// (unless serr is a NULL pointer).
+// The crossing time is returned via parameter jx.
+pub unsafe fn swe_helio_cross(
+    ipl: i32,
+    x2cross: f64,
+    jd_et: f64,
+    iflag: i32,
+    dir: i32,
+//    jd_cross: *mut f64,
+) -> Result<(i32, f64), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut jd_cross: f64 = 0.;
+    let ret_code = raw::swe_helio_cross(
+        ipl,
+        x2cross,
+        jd_et,
+        iflag,
+        dir,
+        &mut jd_cross,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, jd_cross))
+}
+
+pub unsafe fn swe_helio_cross_ut(
+    ipl: i32,
+    x2cross: f64,
+    jd_ut: f64,
+    iflag: i32,
+    dir: i32,
+//    jd_cross: *mut f64,
+) -> Result<(i32, f64), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut jd_cross: f64 = 0.;
+    let ret_code = raw::swe_helio_cross_ut(
+        ipl,
+        x2cross,
+        jd_ut,
+        iflag,
+        dir,
+        &mut jd_cross,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, jd_cross))
+}
+
 //pub fn swe_fixstar(
 //    //star: *mut ::std::os::raw::c_char,
 //    star: &str,
@@ -602,91 +605,108 @@ pub unsafe fn swe_calc_ut(
 //    }
 //}
 //
-//pub fn swe_fixstar2(
-////    star: *mut ::std::os::raw::c_char,
-//    star: &str,
-//    tjd: f64,
-//    iflag: i32,
-////    xx: *mut f64,
-//) -> Result<(i32, String, [f64;6]), (i32, String)>  {
-//    unsafe {
-//        let mut star_buffer = new_max_buffer_from_str(star);
-//        let mut xx: [f64;6] = [0.;6];
-//        let mut serr = new_max_buffer();
-//        let ret_code = raw::swe_fixstar2(
-//            star_buffer.as_mut_ptr(),
-//            tjd,
-//            iflag,
-//            xx.as_mut_ptr(),
-//            serr.as_mut_ptr(),
-//        );
+// The library writes "name,nomenclature" of the star found back into the star argument,
+// each part up to SE_MAX_STNAME bytes
+const STAR_BUFFER: usize = 2 * raw::SE_MAX_STNAME as usize + 2;
+
+fn new_star_buffer(star: &str) -> Result<[c_char; STAR_BUFFER], (i32, String)> {
+    if star.len() > raw::SE_MAX_STNAME as usize || star.contains('\0') {
+        let max = raw::SE_MAX_STNAME;
+        return Err((RAW_ERR, format!("star name longer than {} bytes or with nul: {}", max, star)));
+    }
+
+    let mut buffer = [0; STAR_BUFFER];
+    for (b, &c) in buffer.iter_mut().zip(star.as_bytes()) {
+        *b = c as c_char;
+    }
+    Ok(buffer)
+}
+
+unsafe fn star_buffer_to_string(buffer: &[c_char; STAR_BUFFER]) -> String {
+    CStr::from_ptr(buffer.as_ptr()).to_string_lossy().into_owned()
+}
+
+// star is a name, nomenclature or sequential number in the star file. On return it holds
+// "name,nomenclature" of the star found.
+pub unsafe fn swe_fixstar2(
+    star: &str,
+    tjd: f64,
+    iflag: i32,
+//    xx: *mut f64,
+) -> Result<(i32, String, [f64; 6]), (i32, String)> {
+    let mut star_buffer = new_star_buffer(star)?;
+    let mut xx: [f64; 6] = [0.; 6];
+    let mut serr = new_max_buffer();
+    let ret_code = raw::swe_fixstar2(
+        star_buffer.as_mut_ptr(),
+        tjd,
+        iflag,
+        xx.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, star_buffer_to_string(&star_buffer), xx))
+}
+
+pub unsafe fn swe_fixstar2_ut(
+    star: &str,
+    tjd_ut: f64,
+    iflag: i32,
+//    xx: *mut f64,
+) -> Result<(i32, String, [f64; 6]), (i32, String)> {
+    let mut star_buffer = new_star_buffer(star)?;
+    let mut xx: [f64; 6] = [0.; 6];
+    let mut serr = new_max_buffer();
+    let ret_code = raw::swe_fixstar2_ut(
+        star_buffer.as_mut_ptr(),
+        tjd_ut,
+        iflag,
+        xx.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, star_buffer_to_string(&star_buffer), xx))
+}
+
+pub unsafe fn swe_fixstar2_mag(
+    star: &str,
+//    mag: *mut f64,
+) -> Result<(i32, String, f64), (i32, String)> {
+    let mut star_buffer = new_star_buffer(star)?;
+    let mut mag: f64 = 0.;
+    let mut serr = new_max_buffer();
+    let ret_code = raw::swe_fixstar2_mag(
+        star_buffer.as_mut_ptr(),
+        &mut mag,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, star_buffer_to_string(&star_buffer), mag))
+}
+
 //
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        return Ok((ret_code, buffer_to_string(star_buffer), xx));
-//    }
-//}
-//
-//pub fn swe_fixstar2_ut(
-// //   star: *mut ::std::os::raw::c_char,
-//    star: &str,
-//    tjd_ut: f64,
-//    iflag: i32,
-////    xx: *mut f64,
-//) -> Result<(i32, String, [f64;6]), (i32, String)> {
-//    unsafe {
-//        let mut star_buffer = new_max_buffer_from_str(star);
-//        let mut xx: [f64;6] = [0.;6];
-//        let mut serr = new_max_buffer();
-//        let ret_code = raw::swe_fixstar2_ut(
-//            star_buffer.as_mut_ptr(),
-//            tjd_ut,
-//            iflag,
-//            xx.as_mut_ptr(),
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        return Ok((ret_code, buffer_to_string(star_buffer), xx));
-//    }
-//}
-//
-//pub fn swe_fixstar2_mag(
-//    //star: *mut ::std::os::raw::c_char,
-//    star: &str,
-////    mag: *mut f64,
-//) -> Result<(i32, String, f64), (i32, String)> {
-//    unsafe {
-//        let mut star_buffer = new_max_buffer_from_str(star);
-//        let mut mag: f64 = 0.;
-//        let mut serr = new_max_buffer();
-//        let ret_code = raw::swe_fixstar2_mag(
-//            star_buffer.as_mut_ptr(),
-//            &mut mag,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        return Ok((ret_code, buffer_to_string(star_buffer), mag));
-//    }
-//}
-//
-//
-pub unsafe fn swe_close() {
-    raw::swe_close()
-}
+pub unsafe fn swe_close() {
+    super::bump_generation();
+    super::update_settings(|s| *s = super::Settings::default());
+    raw::swe_close()
+}
 //
 //pub fn swe_set_ephe_path(path: *const ::std::os::raw::c_char) {
 pub unsafe fn swe_set_ephe_path(path: &str) {
     let mut path_buffer = new_max_buffer_from_str(path);
+    super::bump_generation();
     raw::swe_set_ephe_path(path_buffer.as_mut_ptr()) 
 }
 //
@@ -699,21 +719,19 @@ pub unsafe fn swe_set_ephe_path(path: &str) {
 //}
 //
 //
-//pub fn swe_get_planet_name(
-//    ipl: ::std::os::raw::c_int,
-////    spname: *mut ::std::os::raw::c_char,
-////) -> *mut ::std::os::raw::c_char {
-//) -> String {
-//    unsafe {
-//        let mut spname_buffer = new_max_buffer();
-//        raw::swe_get_planet_name(
-//            ipl,
-//            spname_buffer.as_mut_ptr()
-//        );
-//
-//        buffer_to_string(spname_buffer)
-//    }
-//}
+pub unsafe fn swe_get_planet_name(
+    ipl: i32,
+//    spname: *mut ::std::os::raw::c_char,
+//) -> *mut ::std::os::raw::c_char {
+) -> String {
+    let mut spname_buffer = new_max_buffer();
+    let ret = raw::swe_get_planet_name(
+        ipl,
+        spname_buffer.as_mut_ptr()
+    );
+
+    c_chars_to_string(ret)
+}
 //
 //// The function returns either the ephemeris flag used or ERR (-1)
 //pub fn swe_get_ayanamsa_ex(
@@ -739,29 +757,26 @@ pub unsafe fn swe_set_ephe_path(path: &str) {
 //    }
 //}
 //
-//pub fn swe_get_ayanamsa_ex_ut(
-//    tjd_ut: f64,
-//    iflag: i32,
-// //   daya: *mut f64,
-////) -> i32 {
-//) -> Result<(i32, f64),(i32, String)> {
-//    unsafe {
-//        let mut daya: f64 = 0.;
-//        let mut serr = new_max_buffer();
-//        let ret_code = raw::swe_get_ayanamsa_ex_ut(
-//            tjd_ut,
-//            iflag,
-//            &mut daya,
-//            serr.as_mut_ptr(),
-//        );
-//
-//        if ret_code < RAW_OK {
-//            return Err((ret_code, buffer_to_string(serr)));
-//        }
-//
-//        Ok((ret_code, daya))
-//    }
-//}
+pub unsafe fn swe_get_ayanamsa_ex_ut(
+    tjd_ut: f64,
+    iflag: i32,
+//    daya: *mut f64,
+) -> Result<(i32, f64), (i32, String)> {
+    let mut daya: f64 = 0.;
+    let mut serr = new_max_buffer();
+    let ret_code = raw::swe_get_ayanamsa_ex_ut(
+        tjd_ut,
+        iflag,
+        &mut daya,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)));
+    }
+
+    Ok((ret_code, daya))
+}
 //
 //
 //// //pub fn swe_get_ayanamsa_name(isidmode: i32) -> *const ::std::os::raw::c_char {
@@ -885,88 +900,110 @@ pub unsafe fn swe_utc_to_jd(
     Ok((ret_code, dret))
 }
 
-//pub fn swe_jdet_to_utc(
-//    tjd_et: f64,
-//    gregflag: i32,
-//    iyear: *mut i32,
-//    imonth: *mut i32,
-//    iday: *mut i32,
-//    ihour: *mut i32,
-//    imin: *mut i32,
-//    dsec: *mut f64,
-//) {
-//    unsafe {
-//        raw::swe_jdet_to_utc(
-//            tjd_et,
-//            gregflag,
-//            iyear,
-//            imonth,
-//            iday,
-//            ihour,
-//            imin,
-//            dsec,
-//        )
-//    }
-//}
-//
-//pub fn swe_jdut1_to_utc(
-//    tjd_ut: f64,
-//    gregflag: i32,
-//    iyear: *mut i32,
-//    imonth: *mut i32,
-//    iday: *mut i32,
-//    ihour: *mut i32,
-//    imin: *mut i32,
-//    dsec: *mut f64,
-//) {
-//    unsafe {
-//        raw::swe_jdut1_to_utc(
-//            tjd_ut,
-//            gregflag,
-//            iyear,
-//            imonth,
-//            iday,
-//            ihour,
-//            imin,
-//            dsec,
-//        )
-//    }
-//}
-//
-//pub fn swe_utc_time_zone(
-//    iyear: i32,
-//    imonth: i32,
-//    iday: i32,
-//    ihour: i32,
-//    imin: i32,
-//    dsec: f64,
-//    d_timezone: f64,
-//    iyear_out: *mut i32,
-//    imonth_out: *mut i32,
-//    iday_out: *mut i32,
-//    ihour_out: *mut i32,
-//    imin_out: *mut i32,
-//    dsec_out: *mut f64,
-//) {
-//    unsafe {
-//        raw::swe_utc_time_zone(
-//            iyear,
-//            imonth,
-//            iday,
-//            ihour,
-//            imin,
-//            dsec,
-//            d_timezone,
-//            iyear_out,
-//            imonth_out,
-//            iday_out,
-//            ihour_out,
-//            imin_out,
-//            dsec_out,
-//        )
-//    }
-//}
-//
+pub unsafe fn swe_jdet_to_utc(
+    tjd_et: f64,
+    gregflag: i32,
+    //    iyear: *mut i32,
+    //    imonth: *mut i32,
+    //    iday: *mut i32,
+    //    ihour: *mut i32,
+    //    imin: *mut i32,
+    //    dsec: *mut f64,
+) -> (i32, i32, i32, i32, i32, f64) {
+    let mut iyear: i32 = 0;
+    let mut imonth: i32 = 0;
+    let mut iday: i32 = 0;
+    let mut ihour: i32 = 0;
+    let mut imin: i32 = 0;
+    let mut dsec: f64 = 0.;
+
+    raw::swe_jdet_to_utc(
+        tjd_et,
+        gregflag,
+        &mut iyear,
+        &mut imonth,
+        &mut iday,
+        &mut ihour,
+        &mut imin,
+        &mut dsec,
+    );
+
+    (iyear, imonth, iday, ihour, imin, dsec)
+}
+
+pub unsafe fn swe_jdut1_to_utc(
+    tjd_ut: f64,
+    gregflag: i32,
+    //    iyear: *mut i32,
+    //    imonth: *mut i32,
+    //    iday: *mut i32,
+    //    ihour: *mut i32,
+    //    imin: *mut i32,
+    //    dsec: *mut f64,
+) -> (i32, i32, i32, i32, i32, f64) {
+    let mut iyear: i32 = 0;
+    let mut imonth: i32 = 0;
+    let mut iday: i32 = 0;
+    let mut ihour: i32 = 0;
+    let mut imin: i32 = 0;
+    let mut dsec: f64 = 0.;
+
+    raw::swe_jdut1_to_utc(
+        tjd_ut,
+        gregflag,
+        &mut iyear,
+        &mut imonth,
+        &mut iday,
+        &mut ihour,
+        &mut imin,
+        &mut dsec,
+    );
+
+    (iyear, imonth, iday, ihour, imin, dsec)
+}
+
+// d_timezone is the offset of the input time from UTC in hours, e.g. 5.5 for India.
+// The input time is converted to UTC. Pass -d_timezone to convert UTC to local time.
+pub unsafe fn swe_utc_time_zone(
+    iyear: i32,
+    imonth: i32,
+    iday: i32,
+    ihour: i32,
+    imin: i32,
+    dsec: f64,
+    d_timezone: f64,
+    //    iyear_out: *mut i32,
+    //    imonth_out: *mut i32,
+    //    iday_out: *mut i32,
+    //    ihour_out: *mut i32,
+    //    imin_out: *mut i32,
+    //    dsec_out: *mut f64,
+) -> (i32, i32, i32, i32, i32, f64) {
+    let mut iyear_out: i32 = 0;
+    let mut imonth_out: i32 = 0;
+    let mut iday_out: i32 = 0;
+    let mut ihour_out: i32 = 0;
+    let mut imin_out: i32 = 0;
+    let mut dsec_out: f64 = 0.;
+
+    raw::swe_utc_time_zone(
+        iyear,
+        imonth,
+        iday,
+        ihour,
+        imin,
+        dsec,
+        d_timezone,
+        &mut iyear_out,
+        &mut imonth_out,
+        &mut iday_out,
+        &mut ihour_out,
+        &mut imin_out,
+        &mut dsec_out,
+    );
+
+    (iyear_out, imonth_out, iday_out, ihour_out, imin_out, dsec_out)
+}
 
 pub unsafe fn swe_houses(
     tjd_ut: f64,
@@ -1005,27 +1042,30 @@ pub unsafe fn swe_houses(
     };
 }
 
-//pub fn swe_houses_ex(
-//    tjd_ut: f64,
-//    iflag: i32,
-//    geolat: f64,
-//    geolon: f64,
-//    hsys: i32,
+// cusps has room for the 36 Gauquelin sectors, other systems fill 1..=12
+pub unsafe fn swe_houses_ex(
+    tjd_ut: f64,
+    iflag: i32,
+    geolat: f64,
+    geolon: f64,
+    hsys: i32,
 //    cusps: *mut f64,
 //    ascmc: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        raw::swe_houses_ex(
-//            tjd_ut,
-//            iflag,
-//            geolat,
-//            geolon,
-//            hsys,
-//            cusps,
-//            ascmc,
-//        )
-//    }
-//}
+) -> (i32, [f64; 37], [f64; 10]) {
+    let mut cusps: [f64; 37] = [0.; 37];
+    let mut ascmc: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_houses_ex(
+        tjd_ut,
+        iflag,
+        geolat,
+        geolon,
+        hsys,
+        cusps.as_mut_ptr(),
+        ascmc.as_mut_ptr(),
+    );
+
+    (ret_code, cusps, ascmc)
+}
 //
 //pub fn swe_houses_ex2(
 //    tjd_ut: f64,
@@ -1055,25 +1095,28 @@ pub unsafe fn swe_houses(
 //    }
 //}
 //
-//pub fn swe_houses_armc(
-//    armc: f64,
-//    geolat: f64,
-//    eps: f64,
-//    hsys: ::std::os::raw::c_int,
+// cusps has room for the 36 Gauquelin sectors, other systems fill 1..=12
+pub unsafe fn swe_houses_armc(
+    armc: f64,
+    geolat: f64,
+    eps: f64,
+    hsys: i32,
 //    cusps: *mut f64,
 //    ascmc: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        raw::swe_houses_armc(
-//            armc,
-//            geolat,
-//            eps,
-//            hsys,
-//            cusps,
-//            ascmc,
-//        )
-//    }
-//}
+) -> (i32, [f64; 37], [f64; 10]) {
+    let mut cusps: [f64; 37] = [0.; 37];
+    let mut ascmc: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_houses_armc(
+        armc,
+        geolat,
+        eps,
+        hsys,
+        cusps.as_mut_ptr(),
+        ascmc.as_mut_ptr(),
+    );
+
+    (ret_code, cusps, ascmc)
+}
 //
 ////return ::std::os::raw::c_int
 //pub fn swe_houses_armc_ex2(
@@ -1154,251 +1197,323 @@ pub unsafe fn swe_house_name(hsys: i32) -> String {
 //    }
 //}
 //
-//pub fn swe_sol_eclipse_where(
-//    tjd: f64,
-//    ifl: i32,
-//    geopos: *mut f64,
-//    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_sol_eclipse_where(
-//            tjd,
-//            ifl,
-//            geopos,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_occult_where(
-//    tjd: f64,
-//    ipl: i32,
-//    starname: *mut ::std::os::raw::c_char,
-//    ifl: i32,
+pub unsafe fn swe_sol_eclipse_where(
+    tjd: f64,
+    ifl: i32,
 //    geopos: *mut f64,
 //    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_occult_where(
-//            tjd,
-//            ipl,
-//            starname,
-//            ifl,
-//            geopos,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_sol_eclipse_how(
-//    tjd: f64,
-//    ifl: i32,
+) -> Result<(i32, [f64; 10], [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut geopos: [f64; 10] = [0.; 10];
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_sol_eclipse_where(
+        tjd,
+        ifl,
+        geopos.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, geopos, attr))
+}
+
+// starname is "" for planets
+pub unsafe fn swe_lun_occult_where(
+    tjd: f64,
+    ipl: i32,
+    starname: &str,
+    ifl: i32,
 //    geopos: *mut f64,
 //    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_sol_eclipse_how(
-//            tjd,
-//            ifl,
-//            geopos,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_sol_eclipse_when_loc(
-//    tjd_start: f64,
-//    ifl: i32,
-//    geopos: *mut f64,
+) -> Result<(i32, [f64; 10], [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut star = new_star_buffer(starname)?;
+    let mut geopos: [f64; 10] = [0.; 10];
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_lun_occult_where(
+        tjd,
+        ipl,
+        star.as_mut_ptr(),
+        ifl,
+        geopos.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, geopos, attr))
+}
+
+pub unsafe fn swe_sol_eclipse_how(
+    tjd: f64,
+    ifl: i32,
+    mut geopos: [f64; 3],
+//    attr: *mut f64,
+) -> Result<(i32, [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_sol_eclipse_how(
+        tjd,
+        ifl,
+        geopos.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, attr))
+}
+
+pub unsafe fn swe_sol_eclipse_when_loc(
+    tjd_start: f64,
+    ifl: i32,
+    mut geopos: [f64; 3],
 //    tret: *mut f64,
 //    attr: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_sol_eclipse_when_loc(
-//            tjd_start,
-//            ifl,
-//            geopos,
-//            tret,
-//            attr,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_occult_when_loc(
-//    tjd_start: f64,
-//    ipl: i32,
-//    starname: *mut ::std::os::raw::c_char,
-//    ifl: i32,
-//    geopos: *mut f64,
+    backward: i32,
+) -> Result<(i32, [f64; 10], [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut tret: [f64; 10] = [0.; 10];
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_sol_eclipse_when_loc(
+        tjd_start,
+        ifl,
+        geopos.as_mut_ptr(),
+        tret.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret, attr))
+}
+
+// starname is "" for planets
+pub unsafe fn swe_lun_occult_when_loc(
+    tjd_start: f64,
+    ipl: i32,
+    starname: &str,
+    ifl: i32,
+    mut geopos: [f64; 3],
 //    tret: *mut f64,
 //    attr: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_occult_when_loc(
-//            tjd_start,
-//            ipl,
-//            starname,
-//            ifl,
-//            geopos,
-//            tret,
-//            attr,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_sol_eclipse_when_glob(
-//    tjd_start: f64,
-//    ifl: i32,
-//    ifltype: i32,
+    backward: i32,
+) -> Result<(i32, [f64; 10], [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut star = new_star_buffer(starname)?;
+    let mut tret: [f64; 10] = [0.; 10];
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_lun_occult_when_loc(
+        tjd_start,
+        ipl,
+        star.as_mut_ptr(),
+        ifl,
+        geopos.as_mut_ptr(),
+        tret.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret, attr))
+}
+
+pub unsafe fn swe_sol_eclipse_when_glob(
+    tjd_start: f64,
+    ifl: i32,
+    ifltype: i32,
 //    tret: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_sol_eclipse_when_glob(
-//            tjd_start,
-//            ifl,
-//            ifltype,
-//            tret,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_occult_when_glob(
-//    tjd_start: f64,
-//    ipl: i32,
-//    starname: *mut ::std::os::raw::c_char,
-//    ifl: i32,
-//    ifltype: i32,
+    backward: i32,
+) -> Result<(i32, [f64; 10]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut tret: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_sol_eclipse_when_glob(
+        tjd_start,
+        ifl,
+        ifltype,
+        tret.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret))
+}
+
+// starname is "" for planets
+pub unsafe fn swe_lun_occult_when_glob(
+    tjd_start: f64,
+    ipl: i32,
+    starname: &str,
+    ifl: i32,
+    ifltype: i32,
 //    tret: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_occult_when_glob(
-//            tjd_start,
-//            ipl,
-//            starname,
-//            ifl,
-//            ifltype,
-//            tret,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_eclipse_how(
-//    tjd_ut: f64,
-//    ifl: i32,
-//    geopos: *mut f64,
+    backward: i32,
+) -> Result<(i32, [f64; 10]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut star = new_star_buffer(starname)?;
+    let mut tret: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_lun_occult_when_glob(
+        tjd_start,
+        ipl,
+        star.as_mut_ptr(),
+        ifl,
+        ifltype,
+        tret.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret))
+}
+
+pub unsafe fn swe_lun_eclipse_how(
+    tjd_ut: f64,
+    ifl: i32,
+    mut geopos: [f64; 3],
 //    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_eclipse_how(
-//            tjd_ut,
-//            ifl,
-//            geopos,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_eclipse_when(
-//    tjd_start: f64,
-//    ifl: i32,
-//    ifltype: i32,
+) -> Result<(i32, [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_lun_eclipse_how(
+        tjd_ut,
+        ifl,
+        geopos.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, attr))
+}
+
+pub unsafe fn swe_lun_eclipse_when(
+    tjd_start: f64,
+    ifl: i32,
+    ifltype: i32,
 //    tret: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_eclipse_when(
-//            tjd_start,
-//            ifl,
-//            ifltype,
-//            tret,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_lun_eclipse_when_loc(
-//    tjd_start: f64,
-//    ifl: i32,
-//    geopos: *mut f64,
+    backward: i32,
+) -> Result<(i32, [f64; 10]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut tret: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_lun_eclipse_when(
+        tjd_start,
+        ifl,
+        ifltype,
+        tret.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret))
+}
+
+pub unsafe fn swe_lun_eclipse_when_loc(
+    tjd_start: f64,
+    ifl: i32,
+    mut geopos: [f64; 3],
 //    tret: *mut f64,
 //    attr: *mut f64,
-//    backward: i32,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_lun_eclipse_when_loc(
-//            tjd_start,
-//            ifl,
-//            geopos,
-//            tret,
-//            attr,
-//            backward,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_pheno(
-//    tjd: f64,
-//    ipl: i32,
-//    iflag: i32,
+    backward: i32,
+) -> Result<(i32, [f64; 10], [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut tret: [f64; 10] = [0.; 10];
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_lun_eclipse_when_loc(
+        tjd_start,
+        ifl,
+        geopos.as_mut_ptr(),
+        tret.as_mut_ptr(),
+        attr.as_mut_ptr(),
+        backward,
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret, attr))
+}
+
+pub unsafe fn swe_pheno(
+    tjd: f64,
+    ipl: i32,
+    iflag: i32,
 //    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_pheno(
-//            tjd,
-//            ipl,
-//            iflag,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_pheno_ut(
-//    tjd_ut: f64,
-//    ipl: i32,
-//    iflag: i32,
+) -> Result<(i32, [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_pheno(
+        tjd,
+        ipl,
+        iflag,
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, attr))
+}
+
+pub unsafe fn swe_pheno_ut(
+    tjd_ut: f64,
+    ipl: i32,
+    iflag: i32,
 //    attr: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_pheno_ut(
-//            tjd_ut,
-//            ipl,
-//            iflag,
-//            attr,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
+) -> Result<(i32, [f64; 20]), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut attr: [f64; 20] = [0.; 20];
+    let ret_code = raw::swe_pheno_ut(
+        tjd_ut,
+        ipl,
+        iflag,
+        attr.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code < RAW_OK {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, attr))
+}
+
 //
 //pub fn swe_refrac(inalt: f64, atpress: f64, attemp: f64, calc_flag: i32) -> f64 {
 //    unsafe {
@@ -1435,28 +1550,34 @@ pub unsafe fn swe_house_name(hsys: i32) -> String {
 //    }
 //}
 //
-//pub fn swe_azalt(
-//    tjd_ut: f64,
-//    calc_flag: i32,
-//    geopos: *mut f64,
-//    atpress: f64,
-//    attemp: f64,
-//    xin: *mut f64,
-//    xaz: *mut f64,
-//) {
-//    unsafe {
-//        raw::swe_azalt(
-//            tjd_ut,
-//            calc_flag,
-//            geopos,
-//            atpress,
-//            attemp,
-//            xin,
-//            xaz,
-//        )
-//    }
-//}
-//
+// geopos: geographic longitude, latitude and altitude above sea in meters
+// xin: ecliptic or equatorial (see calc_flag) longitude, latitude, distance
+// Returns azimuth (from south, clockwise via west), true altitude and apparent altitude
+pub unsafe fn swe_azalt(
+    tjd_ut: f64,
+    calc_flag: i32,
+    //geopos: *mut f64,
+    mut geopos: [f64;3],
+    atpress: f64,
+    attemp: f64,
+    //xin: *mut f64,
+    mut xin: [f64;3],
+    //xaz: *mut f64,
+) -> [f64;3] {
+    let mut xaz: [f64;3] = [0.;3];
+    raw::swe_azalt(
+        tjd_ut,
+        calc_flag,
+        geopos.as_mut_ptr(),
+        atpress,
+        attemp,
+        xin.as_mut_ptr(),
+        xaz.as_mut_ptr(),
+    );
+
+    xaz
+}
+
 //pub fn swe_azalt_rev(
 //    tjd_ut: f64,
 //    calc_flag: i32,
@@ -1475,64 +1596,80 @@ pub unsafe fn swe_house_name(hsys: i32) -> String {
 //    }
 //}
 //
-//pub fn swe_rise_trans_true_hor(
-//    tjd_ut: f64,
-//    ipl: i32,
-//    starname: *mut ::std::os::raw::c_char,
-//    epheflag: i32,
-//    rsmi: i32,
-//    geopos: *mut f64,
-//    atpress: f64,
-//    attemp: f64,
-//    horhgt: f64,
+// Returns -2 in the Ok code when the body is circumpolar (never rises or never sets) and
+// tret is left at 0
+pub unsafe fn swe_rise_trans_true_hor(
+    tjd_ut: f64,
+    ipl: i32,
+    starname: &str,
+    epheflag: i32,
+    rsmi: i32,
+    mut geopos: [f64; 3],
+    atpress: f64,
+    attemp: f64,
+    horhgt: f64,
 //    tret: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_rise_trans_true_hor(
-//            tjd_ut,
-//            ipl,
-//            starname,
-//            epheflag,
-//            rsmi,
-//            geopos,
-//            atpress,
-//            attemp,
-//            horhgt,
-//            tret,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
-//pub fn swe_rise_trans(
-//    tjd_ut: f64,
-//    ipl: i32,
-//    starname: *mut ::std::os::raw::c_char,
-//    epheflag: i32,
-//    rsmi: i32,
-//    geopos: *mut f64,
-//    atpress: f64,
-//    attemp: f64,
+) -> Result<(i32, f64), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut star = new_star_buffer(starname)?;
+    let mut tret: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_rise_trans_true_hor(
+        tjd_ut,
+        ipl,
+        star.as_mut_ptr(),
+        epheflag,
+        rsmi,
+        geopos.as_mut_ptr(),
+        atpress,
+        attemp,
+        horhgt,
+        tret.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code == RAW_ERR {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret[0]))
+}
+
+// Returns -2 in the Ok code when the body is circumpolar (never rises or never sets) and
+// tret is left at 0
+pub unsafe fn swe_rise_trans(
+    tjd_ut: f64,
+    ipl: i32,
+    starname: &str,
+    epheflag: i32,
+    rsmi: i32,
+    mut geopos: [f64; 3],
+    atpress: f64,
+    attemp: f64,
 //    tret: *mut f64,
-//) -> i32 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_rise_trans(
-//            tjd_ut,
-//            ipl,
-//            starname,
-//            epheflag,
-//            rsmi,
-//            geopos,
-//            atpress,
-//            attemp,
-//            tret,
-//            serr.as_mut_ptr(),
-//        )
-//    }
-//}
-//
+) -> Result<(i32, f64), (i32, String)> {
+    let mut serr = new_max_buffer();
+    let mut star = new_star_buffer(starname)?;
+    let mut tret: [f64; 10] = [0.; 10];
+    let ret_code = raw::swe_rise_trans(
+        tjd_ut,
+        ipl,
+        star.as_mut_ptr(),
+        epheflag,
+        rsmi,
+        geopos.as_mut_ptr(),
+        atpress,
+        attemp,
+        tret.as_mut_ptr(),
+        serr.as_mut_ptr(),
+    );
+
+    if ret_code == RAW_ERR {
+        return Err((ret_code, buffer_to_string(serr)))
+    }
+
+    Ok((ret_code, tret[0]))
+}
+
 //pub fn swe_nod_aps(
 //    tjd_et: f64,
 //    ipl: i32,
@@ -1625,12 +1762,11 @@ pub unsafe fn swe_house_name(hsys: i32) -> String {
 //    }
 //}
 //
-//pub fn swe_deltat_ex(tjd: f64, iflag: i32) -> f64 {
-//    unsafe {
-//        let mut serr = new_max_buffer();
-//        raw::swe_deltat_ex(tjd, iflag, serr.as_mut_ptr())
-//    }
-//}
+// serr only carries a warning when the ephemeris of iflag is not available
+pub unsafe fn swe_deltat_ex(tjd: f64, iflag: i32) -> f64 {
+    let mut serr = new_max_buffer();
+    raw::swe_deltat_ex(tjd, iflag, serr.as_mut_ptr())
+}
 //
 //pub fn swe_time_equ(tjd: f64, te: *mut f64) -> i32 {
 //    unsafe {
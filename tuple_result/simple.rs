@@ -26,10 +26,14 @@ pub unsafe fn swe_julday(
 
 
 pub unsafe fn swe_set_topo(geolon: f64, geolat: f64, geoalt: f64) {
+    super::bump_generation();
+    super::update_settings(|s| s.topo = Some((geolon, geolat, geoalt)));
     raw::swe_set_topo(geolon, geolat, geoalt)   
 }
 
 pub unsafe fn swe_set_sid_mode(sid_mode: i32, t0: f64, ayan_t0: f64) {
+    super::bump_generation();
+    super::update_settings(|s| s.sid_mode = Some((sid_mode, t0, ayan_t0)));
     raw::swe_set_sid_mode(sid_mode, t0, ayan_t0)
 }
 